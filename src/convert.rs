@@ -1,13 +1,18 @@
+use crate::Sign;
+use crate::error::{ParseErrorKind, ParseNumberError, TryFromNumberError};
+use crate::integer::Integer;
+use crate::naturals::Hunk;
 use crate::naturals::Natural;
-use crate::naturals::Natural::{Big, Small};
-use crate::naturals::iter::BitIter;
+use crate::naturals::radix;
+use crate::rational::Rational;
 use std::str::FromStr;
 
+/// Primitives of at most 64 bits fit into a single hunk.
 macro_rules! impl_from_small_primitive {
     ($($t:ty)*) => ($(
         impl From<$t> for Natural {
             fn from(value: $t) -> Self {
-                Small(value as usize)
+                Natural::from_hunk(value as Hunk)
             }
         }
     )*)
@@ -16,10 +21,10 @@ macro_rules! impl_from_small_primitive {
 macro_rules! impl_from_unsigned_primitive {
     ($($t:ty)*) => ($(
         impl TryFrom<$t> for Natural {
-            type Error = ();
+            type Error = TryFromNumberError;
             fn try_from(value: $t) -> Result<Self, Self::Error> {
-                if value <= 0 {
-                    Err(())
+                if value < 0 {
+                    Err(TryFromNumberError::Negative)
                 } else {
                     Ok(value.unsigned_abs().into())
                 }
@@ -28,135 +33,478 @@ macro_rules! impl_from_unsigned_primitive {
     )*)
 }
 
-#[cfg(target_pointer_width = "64")]
-macro_rules! impl_from_big_primitive {
-    ($($t:ty)*) => ($(
-        impl From<$t> for Natural {
-            fn from(value: $t) -> Self {
-                if value <= usize::MAX as $t {
-                    Small(value as usize)
-                } else {
-                    let mut part: usize = value.rem_euclid(0x10000000000000000) as usize;
-                    let mut remaining: $t = value >> 64;
-                    let mut parts: Vec<usize> = vec![part];
-                    while remaining != 0 {
-                        part = remaining.rem_euclid(0x10000000000000000) as usize;
-                        remaining >>= 64;
-                        parts.push(part);
-                    }
-                    Big(parts)
+impl_from_small_primitive! { usize u8 u16 u32 u64 }
+
+impl From<u128> for Natural {
+    fn from(value: u128) -> Self {
+        Natural::from_slice(&[value as Hunk, (value >> Hunk::BITS) as Hunk])
+    }
+}
+
+impl_from_unsigned_primitive! { isize i8 i16 i32 i64 i128 }
+
+/// Conversions back to the primitives. `TryFrom` fails for values that do not fit,
+/// `wrapping_to_*` keeps the low bits like `as` does and `saturating_to_*` clamps to `MAX`.
+macro_rules! impl_to_primitive {
+    ($($t:ident $wrapping:ident $saturating:ident)*) => ($(
+        impl TryFrom<&Natural> for $t {
+            type Error = TryFromNumberError;
+            fn try_from(value: &Natural) -> Result<Self, Self::Error> {
+                match u128_of(value.hunks()) {
+                    Some(low) if low <= <$t>::MAX as u128 => Ok(low as $t),
+                    _ => Err(TryFromNumberError::Overflow),
                 }
             }
         }
+        impl TryFrom<Natural> for $t {
+            type Error = TryFromNumberError;
+            fn try_from(value: Natural) -> Result<Self, Self::Error> {
+                <$t>::try_from(&value)
+            }
+        }
+        impl Natural {
+            pub fn $wrapping(&self) -> $t {
+                low_u128(self.hunks()) as $t
+            }
+            pub fn $saturating(&self) -> $t {
+                <$t>::try_from(self).unwrap_or(<$t>::MAX)
+            }
+        }
     )*)
 }
 
-#[cfg(target_pointer_width = "32")]
-macro_rules! impl_from_big_primitive {
-    ($($t:ty)*) => ($(
-        impl From<$t> for Natural {
-            fn from(value: $t) -> Self {
-                if value <= usize::MAX as $t {
-                    Small(value as usize)
-                } else {
-                    let mut part: usize = value.rem_euclid(0x100000000) as usize;
-                    let mut remaining: $t = value >> 32;
-                    let mut parts: Vec<usize> = vec![part];
-                    while remaining != 0 {
-                        part = remaining.rem_euclid(0x100000000) as usize;
-                        remaining >>= 32;
-                        parts.push(part);
-                    }
-                    Big(parts)
-                }
-            }
+impl_to_primitive! {
+    u8 wrapping_to_u8 saturating_to_u8
+    u16 wrapping_to_u16 saturating_to_u16
+    u32 wrapping_to_u32 saturating_to_u32
+    u64 wrapping_to_u64 saturating_to_u64
+    u128 wrapping_to_u128 saturating_to_u128
+    usize wrapping_to_usize saturating_to_usize
+    i8 wrapping_to_i8 saturating_to_i8
+    i16 wrapping_to_i16 saturating_to_i16
+    i32 wrapping_to_i32 saturating_to_i32
+    i64 wrapping_to_i64 saturating_to_i64
+    i128 wrapping_to_i128 saturating_to_i128
+    isize wrapping_to_isize saturating_to_isize
+}
+
+/// The lowest 128 bits of the number with the given hunks.
+pub(crate) fn low_u128(hunks: &[Hunk]) -> u128 {
+    hunks
+        .iter()
+        .take((u128::BITS / Hunk::BITS) as usize)
+        .enumerate()
+        .fold(0, |acc, (idx, hunk)| {
+            acc | (*hunk as u128) << (idx * Hunk::BITS as usize)
+        })
+}
+
+/// The number with the given trimmed hunks, if it fits into a `u128`.
+pub(crate) fn u128_of(hunks: &[Hunk]) -> Option<u128> {
+    if hunks.len() * Hunk::BITS as usize > u128::BITS as usize {
+        None
+    } else {
+        Some(low_u128(hunks))
+    }
+}
+
+/// Floating point conversions.
+impl Natural {
+    /// The nearest `f64`, ties to even. Values beyond `f64::MAX` become infinity.
+    pub fn to_f64(&self) -> f64 {
+        let bit_len = self.bit_len();
+        if bit_len <= u128::BITS as usize {
+            // The primitive cast already rounds to nearest, ties to even.
+            return low_u128(self.hunks()) as f64;
         }
-    )*)
+        if bit_len > f64::MAX_EXP as usize {
+            return f64::INFINITY;
+        }
+        // The top 64 bits with a sticky bit are plenty for a correct 53 bit rounding, and
+        // scaling by a power of two afterwards is exact.
+        let (top, shift) = top_u64(self.hunks(), bit_len);
+        top as f64 * 2f64.powi(shift as i32)
+    }
+    /// The nearest `f32`, ties to even. Values beyond `f32::MAX` become infinity.
+    pub fn to_f32(&self) -> f32 {
+        if self.bit_len() <= u128::BITS as usize {
+            low_u128(self.hunks()) as f32
+        } else {
+            f32::INFINITY
+        }
+    }
+    /// The integral part of `value`, or [TryFromNumberError::NotFinite] for NaN and the
+    /// infinities. Negative values become zero.
+    pub fn from_f64_truncate(value: f64) -> Result<Natural, TryFromNumberError> {
+        if !value.is_finite() {
+            return Err(TryFromNumberError::NotFinite);
+        }
+        if value < 1.0 {
+            return Ok(Natural::ZERO);
+        }
+        let (mantissa, exponent) = decompose(value);
+        Ok(if exponent < 0 {
+            Natural::new(mantissa >> exponent.unsigned_abs())
+        } else {
+            Natural::new(mantissa) << exponent.unsigned_abs()
+        })
+    }
 }
 
-#[cfg(target_pointer_width = "16")]
-macro_rules! impl_from_big_primitive {
-    ($($t:ty)*) => ($(
-        impl From<$t> for Natural {
-            fn from(value: $t) -> Self {
-                if value <= usize::MAX as $t {
-                    Small(value as usize)
-                } else {
-                    let mut part: usize = value.rem_euclid(0x10000) as usize;
-                    let mut remaining: $t = value >> 16;
-                    let mut parts: Vec<usize> = vec![part];
-                    while remaining != 0 {
-                        part = remaining.rem_euclid(0x10000) as usize;
-                        remaining >>= 16;
-                        parts.push(part);
-                    }
-                    Big(parts)
-                }
-            }
+/// `value = mantissa · 2^exponent` for a finite, non-zero float.
+pub(crate) fn decompose(value: f64) -> (u64, i32) {
+    let bits = value.to_bits();
+    let biased = ((bits >> 52) & 0x7ff) as i32;
+    let fraction = bits & ((1 << 52) - 1);
+    if biased == 0 {
+        (fraction, -1074)
+    } else {
+        (fraction | 1 << 52, biased - 1075)
+    }
+}
+
+/// The 64 most significant bits of a number with `bit_len > 64` bits and the shift that
+/// scales them back. The lowest bit is set if any of the dropped bits is.
+fn top_u64(hunks: &[Hunk], bit_len: usize) -> (u64, usize) {
+    let shift = bit_len - u64::BITS as usize;
+    let (idx, offset) = (shift / Hunk::BITS as usize, shift % Hunk::BITS as usize);
+    let top = (low_u128(&hunks[idx..]) >> offset) as u64;
+    let sticky =
+        hunks[idx] & ((1 << offset) - 1) != 0 || hunks[..idx].iter().any(|hunk| *hunk != 0);
+    (top | sticky as u64, shift)
+}
+
+/// Only integral floats convert, everything else is an error.
+impl TryFrom<f64> for Natural {
+    type Error = TryFromNumberError;
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        if !value.is_finite() {
+            Err(TryFromNumberError::NotFinite)
+        } else if value.fract() != 0.0 {
+            Err(TryFromNumberError::Fractional)
+        } else if value < 0.0 {
+            Err(TryFromNumberError::Negative)
+        } else {
+            Natural::from_f64_truncate(value)
         }
-    )*)
+    }
 }
 
-#[cfg(target_pointer_width = "64")]
-macro_rules! impl_from_primitive {
-    () => {
-        impl_from_small_primitive! { usize u8 u16 u32 u64 }
-        impl_from_big_primitive! { u128 }
-    };
+impl TryFrom<f32> for Natural {
+    type Error = TryFromNumberError;
+    fn try_from(value: f32) -> Result<Self, Self::Error> {
+        Natural::try_from(f64::from(value))
+    }
 }
 
-#[cfg(target_pointer_width = "32")]
-macro_rules! impl_from_primitive {
-    () => {
-        impl_from_small_primitive! { usize u8 u16 u32 }
-        impl_from_big_primitive! {  u64 u128 }
-    };
+/// The order of the words in [Natural::export_words] and [Natural::import_words].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WordOrder {
+    MostSignificantFirst,
+    LeastSignificantFirst,
 }
 
-#[cfg(target_pointer_width = "16")]
-macro_rules! impl_from_primitive {
-    () => {
-        impl_from_small_primitive! { usize u8 u16 }
-        impl_from_big_primitive! {  u32 u64 u128 }
-    };
+/// The order of the bytes within a word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endian {
+    Big,
+    Little,
 }
 
-impl_from_primitive! {}
-impl_from_unsigned_primitive! { isize i8 i16 i32 i64 i128 }
+/// Byte level import and export, independent of the hunk width. Zero has no significant
+/// bytes and is exported as an empty buffer.
+impl Natural {
+    /// The significant bytes, least significant first.
+    pub fn to_bytes_le(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = self
+            .hunks()
+            .iter()
+            .flat_map(|hunk| hunk.to_le_bytes())
+            .collect();
+        while bytes.pop_if(|byte| *byte == 0).is_some() {}
+        bytes
+    }
+    /// The significant bytes, most significant first.
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        let mut bytes = self.to_bytes_le();
+        bytes.reverse();
+        bytes
+    }
+    /// The number with the given bytes, least significant first. Extra zeros are ignored.
+    pub fn from_bytes_le(bytes: &[u8]) -> Natural {
+        bytes
+            .chunks(size_of::<Hunk>())
+            .map(|chunk| {
+                let mut hunk = [0; size_of::<Hunk>()];
+                hunk[..chunk.len()].copy_from_slice(chunk);
+                Hunk::from_le_bytes(hunk)
+            })
+            .collect()
+    }
+    /// The number with the given bytes, most significant first. Extra zeros are ignored.
+    pub fn from_bytes_be(bytes: &[u8]) -> Natural {
+        let mut bytes = bytes.to_vec();
+        bytes.reverse();
+        Natural::from_bytes_le(&bytes)
+    }
+    /// Writes `self` as words of `word_size` bytes, like GMP's `mpz_export`. As few words as
+    /// possible are used, the most significant one is padded with zeros.
+    ///
+    /// # Panics
+    /// If `word_size` is zero.
+    pub fn export_words(&self, word_size: usize, order: WordOrder, endian: Endian) -> Vec<u8> {
+        assert_ne!(word_size, 0, "export_words: word_size must not be zero");
+        let mut bytes = self.to_bytes_le();
+        bytes.resize(bytes.len().next_multiple_of(word_size), 0);
+        if endian == Endian::Big {
+            bytes.chunks_mut(word_size).for_each(<[u8]>::reverse);
+        }
+        if order == WordOrder::MostSignificantFirst {
+            let mut words: Vec<&[u8]> = bytes.chunks(word_size).collect();
+            words.reverse();
+            return words.concat();
+        }
+        bytes
+    }
+    /// Reads words of `word_size` bytes, like GMP's `mpz_import`.
+    /// The inverse of [export_words](Natural::export_words).
+    ///
+    /// # Panics
+    /// If `word_size` is zero or does not divide the length of `bytes`.
+    pub fn import_words(
+        bytes: &[u8],
+        word_size: usize,
+        order: WordOrder,
+        endian: Endian,
+    ) -> Natural {
+        assert_ne!(word_size, 0, "import_words: word_size must not be zero");
+        assert!(
+            bytes.len().is_multiple_of(word_size),
+            "import_words: the buffer must consist of whole words"
+        );
+        let mut words: Vec<&[u8]> = bytes.chunks(word_size).collect();
+        if order == WordOrder::MostSignificantFirst {
+            words.reverse();
+        }
+        let mut bytes = words.concat();
+        if endian == Endian::Big {
+            bytes.chunks_mut(word_size).for_each(<[u8]>::reverse);
+        }
+        Natural::from_bytes_le(&bytes)
+    }
+}
 
-impl FromIterator<usize> for Natural {
-    fn from_iter<T: IntoIterator<Item = usize>>(iter: T) -> Self {
-        Big(iter.into_iter().collect()).trim()
+impl FromIterator<Hunk> for Natural {
+    fn from_iter<T: IntoIterator<Item = Hunk>>(iter: T) -> Self {
+        Natural::from_hunks(iter.into_iter().collect())
     }
 }
 
+/// Parsing from text.
+impl Natural {
+    /// Parses digits in the given radix with an optional leading `+`,
+    /// like [`u64::from_str_radix`]. Letters are accepted in either case.
+    ///
+    /// # Panics
+    /// If `radix` is not in `2..=36`.
+    pub fn from_str_radix(src: &str, radix: u32) -> Result<Natural, ParseNumberError> {
+        Natural::from_ascii_radix(src.as_bytes(), radix)
+    }
+    /// Like [`Natural::from_str_radix`], but for ASCII bytes.
+    pub fn from_ascii_radix(src: &[u8], radix: u32) -> Result<Natural, ParseNumberError> {
+        assert!(
+            (2..=36).contains(&radix),
+            "from_ascii_radix: radix must lie in the range `[2, 36]`"
+        );
+        let offset = sign_len(src)?;
+        parse_digits(src, offset, radix, false)
+    }
+    /// Parses a number the way it is written as a Rust literal: an optional leading `+`,
+    /// an optional `0x`, `0o` or `0b` radix prefix and digits that may be separated by `_`.
+    /// This is what [`FromStr`] for [Natural] does, but for ASCII bytes.
+    pub fn from_ascii(src: &[u8]) -> Result<Natural, ParseNumberError> {
+        let offset = sign_len(src)?;
+        parse_literal(src, offset)
+    }
+}
+
+/// Parsing from text with the same rules as for [Natural], but a leading `-` is allowed.
+impl Integer {
+    /// Parses digits in the given radix with an optional leading `+` or `-`,
+    /// like [`i64::from_str_radix`]. Letters are accepted in either case.
+    ///
+    /// # Panics
+    /// If `radix` is not in `2..=36`.
+    pub fn from_str_radix(src: &str, radix: u32) -> Result<Integer, ParseNumberError> {
+        Integer::from_ascii_radix(src.as_bytes(), radix)
+    }
+    /// Like [`Integer::from_str_radix`], but for ASCII bytes.
+    pub fn from_ascii_radix(src: &[u8], radix: u32) -> Result<Integer, ParseNumberError> {
+        assert!(
+            (2..=36).contains(&radix),
+            "from_ascii_radix: radix must lie in the range `[2, 36]`"
+        );
+        let (sign, offset) = split_sign(src);
+        let magnitude = parse_digits(src, offset, radix, false)?;
+        Ok(Integer::from_sign_magnitude(sign, magnitude))
+    }
+    /// Like [`Natural::from_ascii`], with an optional `-` in front of the radix prefix.
+    /// This is what [`FromStr`] for [Integer] does, but for ASCII bytes.
+    pub fn from_ascii(src: &[u8]) -> Result<Integer, ParseNumberError> {
+        let (sign, offset) = split_sign(src);
+        let magnitude = parse_literal(src, offset)?;
+        Ok(Integer::from_sign_magnitude(sign, magnitude))
+    }
+}
+
+/// The sign given by an optional leading `+` or `-`, and its length.
+fn split_sign(src: &[u8]) -> (Sign, usize) {
+    match src.first() {
+        Some(b'+') => (Sign::Positive, 1),
+        Some(b'-') => (Sign::Negative, 1),
+        _ => (Sign::Positive, 0),
+    }
+}
+
+/// Length of an optional leading `+`. A leading `-` is an error.
+fn sign_len(src: &[u8]) -> Result<usize, ParseNumberError> {
+    match split_sign(src) {
+        (Sign::Negative, _) => Err(ParseNumberError::new(ParseErrorKind::Negative, 0)),
+        (Sign::Positive, len) => Ok(len),
+    }
+}
+
+/// Parses `src[offset..]` as a Rust literal without a sign, see [`Natural::from_ascii`].
+fn parse_literal(src: &[u8], offset: usize) -> Result<Natural, ParseNumberError> {
+    let (radix, offset) = match &src[offset..] {
+        [b'0', b'x' | b'X', ..] => (16, offset + 2),
+        [b'0', b'o' | b'O', ..] => (8, offset + 2),
+        [b'0', b'b' | b'B', ..] => (2, offset + 2),
+        [b'_', ..] => {
+            return Err(ParseNumberError::new(ParseErrorKind::InvalidDigit, offset));
+        }
+        _ => (10, offset),
+    };
+    parse_digits(src, offset, radix, true)
+}
+
+/// Turns the ASCII digits in `src[offset..]` into a [Natural], skipping `_` if `separators`
+/// is set. Error positions are relative to the start of `src`.
+fn parse_digits(
+    src: &[u8],
+    offset: usize,
+    radix: u32,
+    separators: bool,
+) -> Result<Natural, ParseNumberError> {
+    let mut values = Vec::with_capacity(src.len() - offset);
+    for (position, byte) in src.iter().enumerate().skip(offset) {
+        if separators && *byte == b'_' {
+            continue;
+        }
+        let value = char::from(*byte)
+            .to_digit(radix)
+            .ok_or(ParseNumberError::new(
+                ParseErrorKind::InvalidDigit,
+                position,
+            ))?;
+        values.push(value as u8);
+    }
+    if values.is_empty() {
+        return Err(ParseNumberError::new(ParseErrorKind::Empty, src.len()));
+    }
+    Ok(Natural::from_hunks(radix::from_digit_values(
+        &values, radix,
+    )))
+}
+
 impl FromStr for Natural {
-    type Err = ();
+    type Err = ParseNumberError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Natural::from_ascii(s.as_bytes())
+    }
+}
+
+impl FromStr for Integer {
+    type Err = ParseNumberError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Integer::from_ascii(s.as_bytes())
+    }
+}
+/// Parses `numerator/denominator`, like `-3/4`, or a lone integer. The numerator is read
+/// like [`Integer::from_ascii`] and the denominator like [`Natural::from_ascii`], so both may
+/// have radix prefixes and `_` separators. The fraction does not have to be reduced.
+impl FromStr for Rational {
+    type Err = ParseNumberError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        #[cfg(target_pointer_width = "16")]
-        let save_decimal_digits = 4usize;
-        #[cfg(target_pointer_width = "32")]
-        let save_decimal_digits = 9usize;
-        #[cfg(target_pointer_width = "64")]
-        let save_decimal_digits = 19usize;
-        let shift = s.len() % save_decimal_digits;
-        let (mut prefix, mut remainder) = s.split_at_checked(shift).ok_or(())?;
-        let mut n = Small(prefix.parse::<usize>().map_err(|_| ())?);
-        while !remainder.is_empty() {
-            (prefix, remainder) = remainder.split_at_checked(save_decimal_digits).ok_or(())?;
-            n = n * Small(10).pow(Small(save_decimal_digits));
-            n = n + Small(prefix.parse::<usize>().map_err(|_| ())?);
-        }
-        Ok(n)
+        let src = s.as_bytes();
+        let Some(slash) = src.iter().position(|byte| *byte == b'/') else {
+            return Ok(Rational::from(Integer::from_ascii(src)?));
+        };
+        let numerator = Integer::from_ascii(&src[..slash])?;
+        let offset = slash + 1;
+        let denominator = Natural::from_ascii(&src[offset..])
+            .map_err(|error| ParseNumberError::new(*error.kind(), error.position() + offset))?;
+        if denominator == Natural::ZERO {
+            return Err(ParseNumberError::new(
+                ParseErrorKind::ZeroDenominator,
+                offset,
+            ));
+        }
+        Ok(Rational::from_parts(numerator, denominator))
+    }
+}
+
+impl From<Natural> for Vec<Hunk> {
+    fn from(value: Natural) -> Self {
+        match value.into_hunks() {
+            hunks if hunks.is_empty() => vec![0],
+            hunks => hunks,
+        }
+    }
+}
+
+/// The inverse of `Vec<bool>::try_from`, least significant bit first.
+impl From<&[bool]> for Natural {
+    fn from(bits: &[bool]) -> Self {
+        bits.chunks(Hunk::BITS as usize)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .rev()
+                    .fold(0, |hunk: Hunk, bit| hunk << 1 | Hunk::from(*bit))
+            })
+            .collect()
+    }
+}
+
+impl From<Vec<bool>> for Natural {
+    fn from(bits: Vec<bool>) -> Self {
+        Natural::from(bits.as_slice())
+    }
+}
+
+impl TryFrom<Natural> for Vec<bool> {
+    type Error = TryFromNumberError;
+    fn try_from(value: Natural) -> Result<Self, Self::Error> {
+        let size = value
+            .len()
+            .checked_mul(Hunk::BITS as usize * size_of::<bool>());
+        if size.is_none_or(|size| size > isize::MAX.unsigned_abs()) {
+            Err(TryFromNumberError::CapacityOverflow)
+        } else {
+            Ok(value.bits_lsb().collect())
+        }
     }
 }
 #[cfg(test)]
 mod from_str_test {
+    use crate::error::{ParseErrorKind, ParseNumberError};
+    use crate::naturals::Hunk;
+    use crate::naturals::Natural;
     use std::str::FromStr;
     #[test]
     fn from_str_test() {
-        use crate::naturals::Natural;
-        assert_eq!(Natural::from_str("100"), Natural::try_from(100));
+        assert_eq!(Natural::from_str("100").ok(), Natural::try_from(100).ok());
         assert_eq!(
             Natural::from_str("100000000000000000000"),
             Ok(Natural::from(100000000000000000000u128))
@@ -166,39 +514,329 @@ mod from_str_test {
             Ok(Natural::from(1000000000000000000900u128))
         );
         assert_eq!(
-            Natural::from_str("9345623510000000000234500000000900"),
-            Natural::try_from(9345623510000000000234500000000900i128)
+            Natural::from_str("9345623510000000000234500000000900").ok(),
+            Natural::try_from(9345623510000000000234500000000900i128).ok()
         );
         assert_eq!(
             Natural::from_str(&u128::MAX.to_string()),
-            Ok(Natural::Big(vec![usize::MAX, usize::MAX]))
+            Ok(Natural::from_hunks(vec![Hunk::MAX, Hunk::MAX]))
         );
         assert_eq!(
             Natural::from_str("340282366920938463463374607431768211456"),
-            Ok(Natural::Big(vec![0, 0, 1]))
+            Ok(Natural::from_hunks(vec![0, 0, 1]))
+        );
+        assert_eq!(
+            Natural::from_str("1000000000000000000"),
+            Ok(Natural::from(1000000000000000000u64))
+        );
+        assert_eq!(
+            Natural::from_str(""),
+            Err(ParseNumberError::new(ParseErrorKind::Empty, 0))
+        );
+        assert_eq!(
+            Natural::from_str("12a"),
+            Err(ParseNumberError::new(ParseErrorKind::InvalidDigit, 2))
         );
     }
 }
+#[cfg(test)]
+mod from_str_radix_test {
+    use crate::error::{ParseErrorKind, ParseNumberError, TryFromNumberError};
+    use crate::integer::Integer;
+    use crate::naturals::Natural;
+    use crate::rational::Rational;
+    #[test]
+    fn from_str_radix() {
+        assert_eq!(
+            Natural::from_str_radix("ff", 16),
+            Ok(Natural::from_hunk(255))
+        );
+        assert_eq!(
+            Natural::from_str_radix("+FF", 16),
+            Ok(Natural::from_hunk(255))
+        );
+        assert_eq!(
+            Natural::from_str_radix("zz", 36),
+            Ok(Natural::from_hunk(35 * 36 + 35))
+        );
+        let error = |src: &str, radix| Natural::from_str_radix(src, radix).unwrap_err();
+        assert_eq!(*error("-1", 10).kind(), ParseErrorKind::Negative);
+        assert_eq!(error("-1", 10).position(), 0);
+        assert_eq!(*error("12", 2).kind(), ParseErrorKind::InvalidDigit);
+        assert_eq!(error("12", 2).position(), 1);
+        assert_eq!(*error("1_0", 10).kind(), ParseErrorKind::InvalidDigit);
+        assert_eq!(error("1_0", 10).position(), 1);
+        assert_eq!(*error("+", 10).kind(), ParseErrorKind::Empty);
+        assert_eq!(error("+", 10).position(), 1);
+        assert_eq!(
+            Natural::from_str_radix(&"1".repeat(128), 2),
+            Ok(Natural::new(u128::MAX))
+        );
+        assert_eq!(
+            Natural::from_ascii_radix(b"10000000000000000", 16),
+            Ok(Natural::from_hunks(vec![0, 1]))
+        );
+    }
+    #[test]
+    fn literal_style() {
+        assert_eq!("0xDEAD_beef".parse(), Ok(Natural::from_hunk(0xDEAD_BEEF)));
+        assert_eq!("+0o777".parse(), Ok(Natural::from_hunk(0o777)));
+        assert_eq!("0b_1010_1010".parse(), Ok(Natural::from_hunk(0b1010_1010)));
+        assert_eq!("1_000_000".parse(), Ok(Natural::from_hunk(1_000_000)));
+        let error = |src: &str, kind, position| {
+            assert_eq!(
+                src.parse::<Natural>(),
+                Err(ParseNumberError::new(kind, position)),
+                "{src}"
+            )
+        };
+        error("0x", ParseErrorKind::Empty, 2);
+        error("0x_", ParseErrorKind::Empty, 3);
+        error("_1", ParseErrorKind::InvalidDigit, 0);
+        error("+_1", ParseErrorKind::InvalidDigit, 1);
+        error("0b102", ParseErrorKind::InvalidDigit, 4);
+        error("++1", ParseErrorKind::InvalidDigit, 1);
+        error("-0x1", ParseErrorKind::Negative, 0);
+        assert_eq!(
+            Natural::from_ascii(b"0x1_0000_0000_0000_0000"),
+            Ok(Natural::from_hunks(vec![0, 1]))
+        );
+    }
+    #[test]
+    fn integers() {
+        assert_eq!(
+            Integer::from_str_radix("-ff", 16),
+            Ok(Integer::new(-255i16))
+        );
+        assert_eq!(
+            Integer::from_str_radix("+Zz", 36),
+            Ok(Integer::new(1295u16))
+        );
+        assert_eq!("-0x_DEAD_beef".parse(), Ok(Integer::new(-0xDEAD_BEEFi64)));
+        assert_eq!("-0".parse(), Ok(Integer::ZERO));
+        assert_eq!("1_000".parse(), Ok(Integer::new(1000u16)));
+        for value in [i128::MIN, -1, 0, i128::MAX] {
+            assert_eq!(value.to_string().parse(), Ok(Integer::new(value)));
+            let text = format!("{:#x}", Integer::new(value));
+            assert_eq!(text.parse(), Ok(Integer::new(value)), "{text}");
+        }
+        let error = |src: &str, kind, position| {
+            assert_eq!(
+                src.parse::<Integer>(),
+                Err(ParseNumberError::new(kind, position)),
+                "{src}"
+            )
+        };
+        error("-", ParseErrorKind::Empty, 1);
+        error("--1", ParseErrorKind::InvalidDigit, 1);
+        error("-_1", ParseErrorKind::InvalidDigit, 1);
+        error("+-1", ParseErrorKind::InvalidDigit, 1);
+        error("0x-1", ParseErrorKind::InvalidDigit, 2);
+        assert_eq!(
+            Integer::from_ascii_radix(b"-1_0", 10),
+            Err(ParseNumberError::new(ParseErrorKind::InvalidDigit, 2))
+        );
+    }
+    #[test]
+    fn rationals() {
+        assert_eq!("-3/4".parse(), Ok(Rational::new(-3i8, 4u8)));
+        assert_eq!(
+            "6/-0".parse::<Rational>().map(drop).unwrap_err().position(),
+            2
+        );
+        assert_eq!("+10/0x14".parse(), Ok(Rational::new(1u8, 2u8)));
+        assert_eq!("-7".parse(), Ok(Rational::from(-7i8)));
+        assert_eq!("0/5".parse(), Ok(Rational::ZERO));
+        let value = Rational::new(-(Integer::new(1u8) << 100u8), 3u8);
+        assert_eq!(value.to_string().parse(), Ok(value));
+        let error = |src: &str, kind, position| {
+            assert_eq!(
+                src.parse::<Rational>(),
+                Err(ParseNumberError::new(kind, position)),
+                "{src}"
+            )
+        };
+        error("1/0", ParseErrorKind::ZeroDenominator, 2);
+        error("1/0x_0", ParseErrorKind::ZeroDenominator, 2);
+        error("1/-2", ParseErrorKind::Negative, 2);
+        error("1/", ParseErrorKind::Empty, 2);
+        error("/2", ParseErrorKind::Empty, 0);
+        error("1/2/3", ParseErrorKind::InvalidDigit, 3);
+        error("1.5", ParseErrorKind::InvalidDigit, 1);
+        assert_eq!(
+            "1/0".parse::<Rational>().unwrap_err().to_string(),
+            "denominator is zero (at byte 2)"
+        );
+    }
+    #[test]
+    fn errors() {
+        let error = "0x1g".parse::<Natural>().unwrap_err();
+        assert_eq!(error.to_string(), "invalid digit found at byte 3");
+        let error = "+".parse::<Natural>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "cannot parse number without digits (at byte 1)"
+        );
+        let error = "-5".parse::<Natural>().unwrap_err();
+        assert_eq!(error.to_string(), "negative sign not allowed (at byte 0)");
+        let error = "-".parse::<Integer>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "cannot parse number without digits (at byte 1)"
+        );
+        assert_eq!(Natural::try_from(-3i8), Err(TryFromNumberError::Negative));
+        let error: &dyn std::error::Error = &TryFromNumberError::Negative;
+        assert_eq!(
+            error.to_string(),
+            "negative value cannot be represented by the target type"
+        );
+    }
+    #[test]
+    fn to_primitives() {
+        let n = Natural::new(300u16);
+        assert_eq!(u8::try_from(&n), Err(TryFromNumberError::Overflow));
+        assert_eq!(u16::try_from(&n), Ok(300));
+        assert_eq!(i16::try_from(n.clone()), Ok(300));
+        assert_eq!(n.wrapping_to_u8(), 44);
+        assert_eq!(n.wrapping_to_i8(), 44);
+        assert_eq!(n.saturating_to_u8(), u8::MAX);
+        assert_eq!(n.saturating_to_i8(), i8::MAX);
+        let n = Natural::new(u128::MAX);
+        assert_eq!(u128::try_from(&n), Ok(u128::MAX));
+        assert_eq!(i128::try_from(&n), Err(TryFromNumberError::Overflow));
+        assert_eq!(n.wrapping_to_i128(), -1);
+        assert_eq!(n.saturating_to_i64(), i64::MAX);
+        let n = Natural::from_hunks(vec![5, 0, 1]);
+        assert_eq!(u128::try_from(&n), Err(TryFromNumberError::Overflow));
+        assert_eq!(n.wrapping_to_u128(), 5);
+        assert_eq!(n.saturating_to_usize(), usize::MAX);
+        assert_eq!(u64::try_from(Natural::ZERO), Ok(0));
+        assert_eq!(Natural::ZERO.wrapping_to_isize(), 0);
+    }
+    #[test]
+    fn from_signed_primitives() {
+        assert_eq!(Natural::try_from(0i8), Ok(Natural::ZERO));
+        assert_eq!(Natural::try_from(0isize), Ok(Natural::ZERO));
+        assert_eq!(
+            Natural::try_from(i128::MAX),
+            Ok(Natural::new(i128::MAX as u128))
+        );
+        assert_eq!(
+            Natural::try_from(i64::MIN),
+            Err(TryFromNumberError::Negative)
+        );
+    }
+    #[test]
+    fn floats() {
+        assert_eq!(Natural::ZERO.to_f64(), 0.0);
+        assert_eq!(Natural::new(u64::MAX).to_f64(), u64::MAX as f64);
+        assert_eq!(Natural::new(u128::MAX).to_f32(), u128::MAX as f32);
+        // 2^200 + 2^147 lies exactly between two doubles and rounds to the even one,
+        // one more bit below tips it upwards.
+        let tie = (Natural::ONE << 200u32) + (Natural::ONE << 147u32);
+        assert_eq!(tie.to_f64(), 2f64.powi(200));
+        assert_eq!(
+            (tie + Natural::ONE).to_f64(),
+            2f64.powi(200) + 2f64.powi(148)
+        );
+        let tie = (Natural::ONE << 200u32) + (Natural::new(3u8) << 147u32);
+        assert_eq!(tie.to_f64(), 2f64.powi(200) + 2f64.powi(149));
+        assert_eq!((Natural::ONE << 1024u32).to_f64(), f64::INFINITY);
+        let max = Natural::try_from(f64::MAX).unwrap();
+        assert_eq!(max.to_f64(), f64::MAX);
+        assert_eq!(max.bit_len(), 1024);
+        assert_eq!(Natural::ONE.to_f32(), 1.0);
+        assert_eq!((Natural::ONE << 128u32).to_f32(), f32::INFINITY);
 
-impl From<Natural> for Vec<usize> {
-    fn from(value: Natural) -> Self {
-        match value {
-            Small(hunk) => vec![hunk],
-            Big(hunks) => hunks,
+        assert_eq!(Natural::try_from(2f64.powi(70)), Ok(Natural::ONE << 70u32));
+        assert_eq!(Natural::try_from(-0.0), Ok(Natural::ZERO));
+        assert_eq!(Natural::try_from(0.5), Err(TryFromNumberError::Fractional));
+        assert_eq!(Natural::try_from(-2.0), Err(TryFromNumberError::Negative));
+        assert_eq!(
+            Natural::try_from(f64::NAN),
+            Err(TryFromNumberError::NotFinite)
+        );
+        assert_eq!(Natural::try_from(7f32), Ok(Natural::new(7u8)));
+        assert_eq!(Natural::from_f64_truncate(12.9), Ok(Natural::new(12u8)));
+        assert_eq!(Natural::from_f64_truncate(-12.9), Ok(Natural::ZERO));
+        assert_eq!(
+            Natural::from_f64_truncate(f64::MIN_POSITIVE),
+            Ok(Natural::ZERO)
+        );
+        assert_eq!(
+            Natural::from_f64_truncate(1e30),
+            Ok(Natural::new(1_000_000_000_000_000_019_884_624_838_656u128))
+        );
+        for value in [f64::INFINITY, f64::NEG_INFINITY, f64::NAN] {
+            assert_eq!(
+                Natural::from_f64_truncate(value),
+                Err(TryFromNumberError::NotFinite)
+            );
         }
     }
 }
+#[cfg(test)]
+mod bytes_test {
+    use crate::convert::{Endian, WordOrder};
+    use crate::naturals::Natural;
 
-impl TryFrom<Natural> for Vec<bool> {
-    type Error = ();
-    fn try_from(value: Natural) -> Result<Self, Self::Error> {
-        let size = value.len() * size_of::<bool>();
-        if size > isize::MAX.unsigned_abs() {
-            Err(())
-        } else {
-            let mut result: Vec<bool> = BitIter::from(value).collect();
-            result.reverse();
-            Ok(result)
+    #[test]
+    fn bytes() {
+        let value = 0x0102_0304_0506_0708_090a_0b0c_0d0e_0f10u128;
+        let n = Natural::new(value);
+        assert_eq!(n.to_bytes_be(), value.to_be_bytes());
+        assert_eq!(n.to_bytes_le(), value.to_le_bytes());
+        assert_eq!(Natural::from_bytes_be(&value.to_be_bytes()), n);
+        assert_eq!(Natural::from_bytes_le(&value.to_le_bytes()), n);
+        assert_eq!(Natural::new(0x1234u16).to_bytes_be(), [0x12, 0x34]);
+        assert_eq!(
+            Natural::from_bytes_be(&[0, 0, 0x12, 0x34]),
+            Natural::new(0x1234u16)
+        );
+        assert!(Natural::ZERO.to_bytes_le().is_empty());
+        assert_eq!(Natural::from_bytes_le(&[]), Natural::ZERO);
+        let big = Natural::new(u128::MAX) * Natural::new(u128::MAX);
+        assert_eq!(Natural::from_bytes_be(&big.to_bytes_be()), big);
+        assert_eq!(big.to_bytes_le().len(), 32);
+    }
+    #[test]
+    fn words() {
+        use Endian::{Big, Little};
+        use WordOrder::{LeastSignificantFirst, MostSignificantFirst};
+        let n = Natural::new(0x0102_0304_0506u64);
+        let cases = [
+            (MostSignificantFirst, Big, [1, 2, 3, 4, 5, 6]),
+            (MostSignificantFirst, Little, [2, 1, 4, 3, 6, 5]),
+            (LeastSignificantFirst, Big, [5, 6, 3, 4, 1, 2]),
+            (LeastSignificantFirst, Little, [6, 5, 4, 3, 2, 1]),
+        ];
+        for (order, endian, bytes) in cases {
+            assert_eq!(n.export_words(2, order, endian), bytes);
+            assert_eq!(Natural::import_words(&bytes, 2, order, endian), n);
         }
+        assert_eq!(
+            n.export_words(4, MostSignificantFirst, Big),
+            [0, 0, 1, 2, 3, 4, 5, 6]
+        );
+        assert_eq!(
+            n.export_words(4, LeastSignificantFirst, Little),
+            [6, 5, 4, 3, 2, 1, 0, 0]
+        );
+        assert_eq!(
+            Natural::import_words(&[0, 0, 0, 1, 0, 0, 0, 2], 4, LeastSignificantFirst, Big),
+            Natural::new(0x0000_0002_0000_0001u64)
+        );
+        assert!(
+            Natural::ZERO
+                .export_words(8, MostSignificantFirst, Big)
+                .is_empty()
+        );
+    }
+    #[test]
+    fn bools() {
+        let n = Natural::new(0b1011_0000u8) << 100u8;
+        let bits = Vec::<bool>::try_from(n.clone()).unwrap();
+        assert_eq!(Natural::from(bits), n);
+        assert_eq!(Natural::from(&[true, false, true][..]), Natural::new(5u8));
     }
 }
@@ -0,0 +1,100 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Why parsing a number from text failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// There are no digits to parse.
+    Empty,
+    /// A byte is neither a digit in the radix nor an allowed separator.
+    InvalidDigit,
+    /// The text starts with a minus sign.
+    Negative,
+    /// The denominator of a [Rational](crate::rational::Rational) is zero.
+    ZeroDenominator,
+}
+
+/// The error returned when parsing a [Natural](crate::naturals::Natural), an
+/// [Integer](crate::integer::Integer) or a [Rational](crate::rational::Rational) from text fails.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParseNumberError {
+    kind: ParseErrorKind,
+    position: usize,
+}
+
+impl ParseNumberError {
+    pub(crate) fn new(kind: ParseErrorKind, position: usize) -> Self {
+        Self { kind, position }
+    }
+    pub fn kind(&self) -> &ParseErrorKind {
+        &self.kind
+    }
+    /// Byte offset into the input at which the problem was found.
+    /// For [ParseErrorKind::Empty] this is where the first digit was expected.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl Display for ParseNumberError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            ParseErrorKind::Empty => write!(
+                f,
+                "cannot parse number without digits (at byte {})",
+                self.position
+            ),
+            ParseErrorKind::InvalidDigit => {
+                write!(f, "invalid digit found at byte {}", self.position)
+            }
+            ParseErrorKind::Negative => {
+                write!(f, "negative sign not allowed (at byte {})", self.position)
+            }
+            ParseErrorKind::ZeroDenominator => {
+                write!(f, "denominator is zero (at byte {})", self.position)
+            }
+        }
+    }
+}
+
+impl Error for ParseNumberError {}
+
+/// The error returned when a conversion into or out of a [Natural](crate::naturals::Natural),
+/// an [Integer](crate::integer::Integer) or a [Rational](crate::rational::Rational) cannot
+/// represent the value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TryFromNumberError {
+    /// The value is negative, but the target has no negative values.
+    Negative,
+    /// The value is larger than the largest value of the target.
+    Overflow,
+    /// The value is smaller than the smallest value of the target.
+    Underflow,
+    /// The result would need more than `isize::MAX` bytes.
+    CapacityOverflow,
+    /// The float has a fractional part.
+    Fractional,
+    /// The float is infinite or NaN.
+    NotFinite,
+}
+
+impl Display for TryFromNumberError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TryFromNumberError::Negative => {
+                f.write_str("negative value cannot be represented by the target type")
+            }
+            TryFromNumberError::Overflow => f.write_str("value is too large for the target type"),
+            TryFromNumberError::Underflow => f.write_str("value is too small for the target type"),
+            TryFromNumberError::CapacityOverflow => {
+                f.write_str("conversion would need more than isize::MAX bytes")
+            }
+            TryFromNumberError::Fractional => f.write_str("float is not an integer"),
+            TryFromNumberError::NotFinite => f.write_str("float is infinite or NaN"),
+        }
+    }
+}
+
+impl Error for TryFromNumberError {}
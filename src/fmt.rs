@@ -1,45 +1,289 @@
-// impl Binary for Natural {
-//     fn fmt(&self, _f: &mut Formatter<'_>) -> std::fmt::Result {
-//         todo!()
-//     }
-// }
-// impl Debug for Naturals {
-//     fn fmt(&self, _f: &mut Formatter<'_>) -> std::fmt::Result {
-//         todo!()
-//     }
-// }
-// impl Display for Natural {
-//     fn fmt(&self, _f: &mut Formatter<'_>) -> std::fmt::Result {
-//         todo!()
-//     }
-// }
-// impl LowerExp for Natural {
-//     fn fmt(&self, _f: &mut Formatter<'_>) -> std::fmt::Result {
-//         todo!()
-//     }
-// }
-// impl LowerHex for Natural {
-//     fn fmt(&self, _f: &mut Formatter<'_>) -> std::fmt::Result {
-//         todo!()
-//     }
-// }
-// impl Octal for Natural {
-//     fn fmt(&self, _f: &mut Formatter<'_>) -> std::fmt::Result {
-//         todo!()
-//     }
-// }
-// impl Pointer for Natural {
-//     fn fmt(&self, _f: &mut Formatter<'_>) -> std::fmt::Result {
-//         todo!()
-//     }
-// }
-// impl UpperExp for Natural {
-//     fn fmt(&self, _f: &mut Formatter<'_>) -> std::fmt::Result {
-//         todo!()
-//     }
-// }
-// impl UpperHex for Natural {
-//     fn fmt(&self, _f: &mut Formatter<'_>) -> std::fmt::Result {
-//         todo!()
-//     }
-// }
+use crate::integer::Integer;
+use crate::naturals::Natural;
+use crate::naturals::radix;
+use crate::rational::Rational;
+use std::fmt::{Binary, Debug, Display, Formatter, LowerExp, LowerHex, Octal, UpperExp, UpperHex};
+
+impl Binary for Natural {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.pad_integral(true, "0b", &radix::to_digits(self.hunks(), 2))
+    }
+}
+impl Display for Natural {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.pad_integral(true, "", &radix::to_digits(self.hunks(), 10))
+    }
+}
+impl LowerExp for Natural {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.pad_integral(true, "", &exp_str(self, f.precision(), 'e'))
+    }
+}
+impl LowerHex for Natural {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.pad_integral(true, "0x", &radix::to_digits(self.hunks(), 16))
+    }
+}
+impl Octal for Natural {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.pad_integral(true, "0o", &radix::to_digits(self.hunks(), 8))
+    }
+}
+impl UpperExp for Natural {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.pad_integral(true, "", &exp_str(self, f.precision(), 'E'))
+    }
+}
+impl UpperHex for Natural {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let digits = radix::to_digits(self.hunks(), 16).to_ascii_uppercase();
+        f.pad_integral(true, "0x", &digits)
+    }
+}
+
+/// Integers are written as a sign and the digits of the magnitude, so negative values get a
+/// `-` in every radix instead of the two's complement the primitive integers print.
+impl Binary for Integer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let digits = radix::to_digits(self.magnitude.hunks(), 2);
+        f.pad_integral(!self.is_negative(), "0b", &digits)
+    }
+}
+/// The fields, like [Natural]'s `Debug`, so `{:x?}` shows the hunks in hex.
+impl Debug for Integer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Integer")
+            .field("sign", &self.sign)
+            .field("magnitude", &self.magnitude)
+            .finish()
+    }
+}
+impl Display for Integer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let digits = radix::to_digits(self.magnitude.hunks(), 10);
+        f.pad_integral(!self.is_negative(), "", &digits)
+    }
+}
+impl LowerExp for Integer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let digits = exp_str(&self.magnitude, f.precision(), 'e');
+        f.pad_integral(!self.is_negative(), "", &digits)
+    }
+}
+impl LowerHex for Integer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let digits = radix::to_digits(self.magnitude.hunks(), 16);
+        f.pad_integral(!self.is_negative(), "0x", &digits)
+    }
+}
+impl Octal for Integer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let digits = radix::to_digits(self.magnitude.hunks(), 8);
+        f.pad_integral(!self.is_negative(), "0o", &digits)
+    }
+}
+impl UpperExp for Integer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let digits = exp_str(&self.magnitude, f.precision(), 'E');
+        f.pad_integral(!self.is_negative(), "", &digits)
+    }
+}
+impl UpperHex for Integer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let digits = radix::to_digits(self.magnitude.hunks(), 16).to_ascii_uppercase();
+        f.pad_integral(!self.is_negative(), "0x", &digits)
+    }
+}
+
+/// `numerator/denominator`, or only the numerator for integers. Sign and padding flags
+/// apply to the whole fraction.
+impl Display for Rational {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut digits = radix::to_digits(self.numerator().magnitude().hunks(), 10);
+        if !self.is_integer() {
+            digits.push('/');
+            digits.push_str(&radix::to_digits(self.denominator().hunks(), 10));
+        }
+        f.pad_integral(!self.is_negative(), "", &digits)
+    }
+}
+impl Debug for Rational {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+/// Scientific notation the way the primitive integers do it: trailing zeros are dropped
+/// unless a precision asks for them, and cut off digits round half to even.
+fn exp_str(n: &Natural, precision: Option<usize>, marker: char) -> String {
+    let mut digits = radix::to_digits(n.hunks(), 10).into_bytes();
+    let mut exponent = digits.len() - 1;
+    let significant = digits.iter().rposition(|digit| *digit != b'0').unwrap_or(0) + 1;
+    digits.truncate(significant);
+    if let Some(precision) = precision {
+        if digits.len() > precision + 1 {
+            let rest = digits.split_off(precision + 1);
+            let odd = (digits[precision] - b'0') % 2 == 1;
+            if rest[0] > b'5' || (rest[0] == b'5' && (rest.len() > 1 || odd)) {
+                round_up(&mut digits, &mut exponent);
+            }
+        }
+        digits.resize(precision + 1, b'0');
+    }
+    let mut s = String::with_capacity(digits.len() + 24);
+    s.push(digits[0] as char);
+    if digits.len() > 1 {
+        s.push('.');
+        s.extend(digits[1..].iter().map(|digit| *digit as char));
+    }
+    s.push(marker);
+    s.push_str(&exponent.to_string());
+    s
+}
+
+/// Adds one unit in the last place. A carry out of the first digit moves the exponent instead.
+fn round_up(digits: &mut [u8], exponent: &mut usize) {
+    for digit in digits.iter_mut().rev() {
+        if *digit == b'9' {
+            *digit = b'0';
+        } else {
+            *digit += 1;
+            return;
+        }
+    }
+    digits[0] = b'1';
+    *exponent += 1;
+}
+
+#[cfg(test)]
+mod fmt_tests {
+    use crate::integer::Integer;
+    use crate::naturals::Natural;
+    use crate::rational::Rational;
+
+    const SAMPLES: [u128; 12] = [
+        0,
+        1,
+        7,
+        10,
+        1234,
+        1235,
+        1225,
+        19999,
+        100500,
+        u64::MAX as u128,
+        1 << 64,
+        u128::MAX,
+    ];
+
+    #[test]
+    fn radix_formats_match_primitives() {
+        for value in SAMPLES {
+            let n = Natural::new(value);
+            assert_eq!(format!("{n}"), format!("{value}"));
+            assert_eq!(format!("{n:b}"), format!("{value:b}"));
+            assert_eq!(format!("{n:o}"), format!("{value:o}"));
+            assert_eq!(format!("{n:x}"), format!("{value:x}"));
+            assert_eq!(format!("{n:X}"), format!("{value:X}"));
+        }
+    }
+    #[test]
+    fn flags_match_primitives() {
+        for value in SAMPLES {
+            let n = Natural::new(value);
+            assert_eq!(format!("{n:>45}"), format!("{value:>45}"));
+            assert_eq!(format!("{n:*^45}"), format!("{value:*^45}"));
+            assert_eq!(format!("{n:<+45}"), format!("{value:<+45}"));
+            assert_eq!(format!("{n:045}"), format!("{value:045}"));
+            assert_eq!(format!("{n:+045}"), format!("{value:+045}"));
+            assert_eq!(format!("{n:#b}"), format!("{value:#b}"));
+            assert_eq!(format!("{n:#o}"), format!("{value:#o}"));
+            assert_eq!(format!("{n:#040x}"), format!("{value:#040x}"));
+            assert_eq!(format!("{n:#X}"), format!("{value:#X}"));
+        }
+    }
+    #[test]
+    fn exp_matches_primitives() {
+        for value in SAMPLES {
+            let n = Natural::new(value);
+            assert_eq!(format!("{n:e}"), format!("{value:e}"));
+            assert_eq!(format!("{n:E}"), format!("{value:E}"));
+            assert_eq!(format!("{n:+012e}"), format!("{value:+012e}"));
+            assert_eq!(format!("{n:>20E}"), format!("{value:>20E}"));
+            for precision in 0..6 {
+                assert_eq!(
+                    format!("{n:.precision$e}"),
+                    format!("{value:.precision$e}"),
+                    "{value} with precision {precision}"
+                );
+            }
+        }
+    }
+    #[test]
+    fn big_values() {
+        let n = Natural::from_hunks(vec![0, 0, 1]);
+        assert_eq!(format!("{n}"), "340282366920938463463374607431768211456");
+        assert_eq!(format!("{n:x}"), format!("1{}", "0".repeat(32)));
+        assert_eq!(format!("{n:o}"), format!("4{}", "0".repeat(42)));
+        assert_eq!(format!("{n:.3e}"), "3.403e38");
+        assert_eq!(
+            format!("{:e}", Natural::from_hunk(10).pow(Natural::from_hunk(100))),
+            "1e100"
+        );
+    }
+    #[test]
+    fn integers_match_primitives() {
+        for value in SAMPLES.map(|value| value as i128).into_iter().chain([
+            -1,
+            -10,
+            -1234,
+            -1235,
+            i64::MIN as i128,
+            i128::MIN,
+        ]) {
+            let n = Integer::new(value);
+            assert_eq!(format!("{n}"), format!("{value}"));
+            assert_eq!(format!("{n:>45}"), format!("{value:>45}"));
+            assert_eq!(format!("{n:+045}"), format!("{value:+045}"));
+            assert_eq!(format!("{n:<+45}"), format!("{value:<+45}"));
+            assert_eq!(format!("{n:.2e}"), format!("{value:.2e}"));
+            assert_eq!(format!("{n:E}"), format!("{value:E}"));
+            let abs = value.unsigned_abs();
+            let sign = if value < 0 { "-" } else { "" };
+            assert_eq!(format!("{n:x}"), format!("{sign}{abs:x}"));
+            assert_eq!(format!("{n:#X}"), format!("{sign}{abs:#X}"));
+            assert_eq!(format!("{n:#o}"), format!("{sign}{abs:#o}"));
+            assert_eq!(format!("{n:b}"), format!("{sign}{abs:b}"));
+        }
+    }
+    #[test]
+    fn negative_radix_padding() {
+        let n = Integer::new(-255i16);
+        assert_eq!(format!("{n:#010x}"), "-0x00000ff");
+        assert_eq!(format!("{n:>8X}"), "     -FF");
+        assert_eq!(format!("{n:+#b}"), "-0b11111111");
+        assert_eq!(format!("{:+}", Integer::new(0u8)), "+0");
+        let big = -Integer::new(Natural::from_hunks(vec![0, 0, 1]));
+        assert_eq!(format!("{big:x}"), format!("-1{}", "0".repeat(32)));
+        assert_eq!(
+            format!("{big:?}"),
+            "Integer { sign: Some(Negative), magnitude: Natural([0, 0, 1]) }"
+        );
+        assert_eq!(
+            format!("{n:X?}"),
+            "Integer { sign: Some(Negative), magnitude: Natural([FF]) }"
+        );
+        assert_eq!(
+            format!("{:?}", Integer::ZERO),
+            "Integer { sign: None, magnitude: Natural([]) }"
+        );
+    }
+    #[test]
+    fn rationals() {
+        assert_eq!(Rational::new(-3i8, 4u8).to_string(), "-3/4");
+        assert_eq!(Rational::new(10u8, -5i8).to_string(), "-2");
+        assert_eq!(format!("{:?}", Rational::ZERO), "0");
+        assert_eq!(format!("{:+}", Rational::new(1u8, 3u8)), "+1/3");
+        assert_eq!(format!("{:>6}", Rational::new(-1i8, 3u8)), "  -1/3");
+    }
+}
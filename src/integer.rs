@@ -1,36 +1,149 @@
 use crate::Sign;
-use std::ops::ShrAssign;
+use crate::convert::{low_u128, u128_of};
+use crate::error::TryFromNumberError;
+use crate::naturals::Natural;
+use std::cmp::Ordering;
+use std::ops::{
+    Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Div, DivAssign,
+    Mul, MulAssign, Neg, Not, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign,
+};
 
+/// An arbitrarily large signed integer. The arithmetic works on the sign and the [Natural]
+/// magnitude, so it uses the same multiplication and long division as [Natural].
+///
+/// Zero has no sign and every other value has a nonzero magnitude, so each number has
+/// exactly one representation. Equality and hashing compare the fields directly.
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct Integer {
     pub(crate) sign: Option<Sign>,
-    pub(crate) parts: Vec<usize>,
+    pub(crate) magnitude: Natural,
 }
 
 impl Integer {
     pub fn new(n: impl Into<Integer>) -> Self {
         n.into()
     }
+    /// `magnitude` with the given sign. Zero gets no sign.
+    pub(crate) fn from_sign_magnitude(sign: Sign, magnitude: Natural) -> Self {
+        let sign = (magnitude != Natural::ZERO).then_some(sign);
+        Self { sign, magnitude }
+    }
+    /// The sign, or `None` for zero.
+    pub fn sign(&self) -> Option<Sign> {
+        self.sign
+    }
+    /// The absolute value as a [Natural].
+    pub fn magnitude(&self) -> &Natural {
+        &self.magnitude
+    }
+    /// Like [sign](Integer::sign), with zero counted as positive.
+    fn signed(&self) -> Sign {
+        self.sign.unwrap_or(Sign::Positive)
+    }
+    /// Drops the sign once the magnitude became zero.
+    fn trim_sign(&mut self) {
+        if self.magnitude == Natural::ZERO {
+            self.sign = None;
+        }
+    }
+}
+
+/// Constants
+impl Integer {
+    pub const ZERO: Integer = Integer {
+        sign: None,
+        magnitude: Natural::ZERO,
+    };
+    pub const ONE: Integer = Integer {
+        sign: Some(Sign::Positive),
+        magnitude: Natural::ONE,
+    };
+    pub const NEG_ONE: Integer = Integer {
+        sign: Some(Sign::Negative),
+        magnitude: Natural::ONE,
+    };
+}
+
+/// Floating point conversions, rounded like the ones for [Natural].
+impl Integer {
+    pub fn to_f64(&self) -> f64 {
+        match self.sign {
+            Some(Sign::Negative) => -self.magnitude.to_f64(),
+            _ => self.magnitude.to_f64(),
+        }
+    }
+    pub fn to_f32(&self) -> f32 {
+        match self.sign {
+            Some(Sign::Negative) => -self.magnitude.to_f32(),
+            _ => self.magnitude.to_f32(),
+        }
+    }
+    /// The integral part of `value`, rounded toward zero, or
+    /// [TryFromNumberError::NotFinite] for NaN and the infinities.
+    pub fn from_f64_truncate(value: f64) -> Result<Integer, TryFromNumberError> {
+        let sign = if value < 0.0 {
+            Sign::Negative
+        } else {
+            Sign::Positive
+        };
+        let magnitude = Natural::from_f64_truncate(value.abs())?;
+        Ok(Integer::from_sign_magnitude(sign, magnitude))
+    }
+}
+
+/// Only integral floats convert, everything else is an error.
+impl TryFrom<f64> for Integer {
+    type Error = TryFromNumberError;
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        if !value.is_finite() {
+            Err(TryFromNumberError::NotFinite)
+        } else if value.fract() != 0.0 {
+            Err(TryFromNumberError::Fractional)
+        } else {
+            Integer::from_f64_truncate(value)
+        }
+    }
+}
+
+impl TryFrom<f32> for Integer {
+    type Error = TryFromNumberError;
+    fn try_from(value: f32) -> Result<Self, Self::Error> {
+        Integer::try_from(f64::from(value))
+    }
+}
+
+impl From<Natural> for Integer {
+    fn from(value: Natural) -> Self {
+        Integer::from_sign_magnitude(Sign::Positive, value)
+    }
+}
+
+/// Fails with [TryFromNumberError::Negative] for negative values.
+impl TryFrom<Integer> for Natural {
+    type Error = TryFromNumberError;
+    fn try_from(value: Integer) -> Result<Self, Self::Error> {
+        match value.sign {
+            Some(Sign::Negative) => Err(TryFromNumberError::Negative),
+            _ => Ok(value.magnitude),
+        }
+    }
+}
+
+impl TryFrom<&Integer> for Natural {
+    type Error = TryFromNumberError;
+    fn try_from(value: &Integer) -> Result<Self, Self::Error> {
+        match value.sign {
+            Some(Sign::Negative) => Err(TryFromNumberError::Negative),
+            _ => Ok(value.magnitude.clone()),
+        }
+    }
 }
 
 macro_rules! from_unsigned_primitive {
     ($($t:ty)*) => ($(
         impl From<$t> for Integer {
             fn from(value: $t) -> Self {
-                let sign =
-                    if value == 0 { None }
-                    else { Some(Sign::Positive) };
-                if size_of::<$t>() <= size_of::<usize>() {
-                    Self { sign, parts: vec![value as usize] }
-                } else {
-                    let mut value = value;
-                    let parts: Vec<usize> = (0..<$t>::BITS.div_euclid(usize::BITS))
-                        .map(|_| {
-                        let part = value.rem_euclid(usize::BITS as $t);
-                        value.shr_assign(usize::BITS as $t);
-                        part as usize
-                    }).collect();
-                    Self { sign, parts }
-                }
+                Integer::from(Natural::from(value))
             }
         }
     )*)
@@ -38,35 +151,17 @@ macro_rules! from_unsigned_primitive {
 
 from_unsigned_primitive! { u8 u16 u32 u64 u128 usize }
 
+/// The magnitude goes through `unsigned_abs`, so `MIN` converts without overflow.
 macro_rules! from_signed_primitive {
     ($($t:ty)*) => ($(
         impl From<$t> for Integer {
             fn from(value: $t) -> Self {
-                let sign =
-                    if value == 0 { None }
-                    else if value < 0 { Some(Sign::Negative) }
-                    else { Some(Sign::Positive) };
-                let value = value.unsigned_abs();
-                if size_of::<$t>() <= size_of::<usize>() {
-                    let mut value = value as usize;
-                    if let Some(Sign::Negative) = sign {
-                        value = value as usize ^ usize::MAX;
-                    }
-                    Self { sign, parts: vec![value as usize] }
+                let sign = if value < 0 {
+                    Sign::Negative
                 } else {
-                    let mut value = value as usize;
-                    if let Some(Sign::Negative) = sign {
-                        value = value as usize ^ usize::MAX;
-                    }
-                    let parts: Vec<usize> = (0..<$t>::BITS.div_euclid(usize::BITS))
-                        .map(|_| {
-                        dbg!(value);
-                        let part = dbg!(value.rem_euclid(usize::BITS.try_into().unwrap()));
-                        value.shr_assign(usize::BITS as $t);
-                        part as usize
-                    }).collect();
-                    Self { sign, parts }
-                }
+                    Sign::Positive
+                };
+                Integer::from_sign_magnitude(sign, Natural::from(value.unsigned_abs()))
             }
         }
     )*)
@@ -74,3 +169,446 @@ macro_rules! from_signed_primitive {
 
 from_signed_primitive! { i8 i16 i32 i64 i128 isize }
 
+/// Conversions back to the primitives, see the ones for [Natural]. Negative values wrap
+/// like two's complement and saturate to `MIN`.
+macro_rules! impl_to_primitive {
+    ($($t:ident $wrapping:ident $saturating:ident)*) => ($(
+        impl TryFrom<&Integer> for $t {
+            type Error = TryFromNumberError;
+            fn try_from(value: &Integer) -> Result<Self, Self::Error> {
+                let magnitude = u128_of(value.magnitude.hunks());
+                match value.sign {
+                    Some(Sign::Negative) if <$t>::MIN == 0 => Err(TryFromNumberError::Negative),
+                    Some(Sign::Negative) => match magnitude {
+                        Some(low) if low <= (<$t>::MIN as i128).unsigned_abs() => {
+                            Ok((low as $t).wrapping_neg())
+                        }
+                        _ => Err(TryFromNumberError::Underflow),
+                    },
+                    _ => match magnitude {
+                        Some(low) if low <= <$t>::MAX as u128 => Ok(low as $t),
+                        _ => Err(TryFromNumberError::Overflow),
+                    },
+                }
+            }
+        }
+        impl TryFrom<Integer> for $t {
+            type Error = TryFromNumberError;
+            fn try_from(value: Integer) -> Result<Self, Self::Error> {
+                <$t>::try_from(&value)
+            }
+        }
+        impl Integer {
+            pub fn $wrapping(&self) -> $t {
+                let low = low_u128(self.magnitude.hunks()) as $t;
+                match self.sign {
+                    Some(Sign::Negative) => low.wrapping_neg(),
+                    _ => low,
+                }
+            }
+            pub fn $saturating(&self) -> $t {
+                match <$t>::try_from(self) {
+                    Ok(value) => value,
+                    Err(TryFromNumberError::Overflow) => <$t>::MAX,
+                    Err(_) => <$t>::MIN,
+                }
+            }
+        }
+    )*)
+}
+
+impl_to_primitive! {
+    u8 wrapping_to_u8 saturating_to_u8
+    u16 wrapping_to_u16 saturating_to_u16
+    u32 wrapping_to_u32 saturating_to_u32
+    u64 wrapping_to_u64 saturating_to_u64
+    u128 wrapping_to_u128 saturating_to_u128
+    usize wrapping_to_usize saturating_to_usize
+    i8 wrapping_to_i8 saturating_to_i8
+    i16 wrapping_to_i16 saturating_to_i16
+    i32 wrapping_to_i32 saturating_to_i32
+    i64 wrapping_to_i64 saturating_to_i64
+    i128 wrapping_to_i128 saturating_to_i128
+    isize wrapping_to_isize saturating_to_isize
+}
+
+impl Integer {
+    /// The absolute value.
+    pub fn abs(&self) -> Integer {
+        Integer::from(self.magnitude.clone())
+    }
+    /// `-1`, `0` or `1`, depending on the sign of `self`.
+    pub fn signum(&self) -> Integer {
+        match self.sign {
+            None => Integer::ZERO,
+            Some(sign) => Integer::from_sign_magnitude(sign, Natural::ONE),
+        }
+    }
+    pub fn is_negative(&self) -> bool {
+        self.sign == Some(Sign::Negative)
+    }
+    pub fn is_positive(&self) -> bool {
+        self.sign == Some(Sign::Positive)
+    }
+    pub fn is_zero(&self) -> bool {
+        self.sign.is_none()
+    }
+    /// Exponentiation, see [Natural::pow]. Odd powers keep the sign of `self`.
+    pub fn pow(self, exp: Natural) -> Integer {
+        let sign = if exp.first_hunk() & 1 == 1 {
+            self.signed()
+        } else {
+            Sign::Positive
+        };
+        Integer::from_sign_magnitude(sign, self.magnitude.pow(exp))
+    }
+    /// Quotient and remainder of `self / rhs`. The quotient is rounded toward zero, so the
+    /// remainder has the sign of `self`, like `/` and `%` on the primitive integers.
+    ///
+    /// # Panics
+    /// If `rhs` is zero.
+    pub fn div_rem(&self, rhs: &Integer) -> (Integer, Integer) {
+        let (quotient, remainder) = self.magnitude.div_rem(&rhs.magnitude);
+        (
+            Integer::from_sign_magnitude(self.signed() * rhs.signed(), quotient),
+            Integer::from_sign_magnitude(self.signed(), remainder),
+        )
+    }
+}
+
+/// Division with the other rounding modes. Each one starts from the truncated
+/// [div_rem](Integer::div_rem) and moves the quotient by one where the rounding differs,
+/// so `self == quotient * rhs + remainder` holds for all of them.
+///
+/// # Panics
+/// If `rhs` is zero.
+impl Integer {
+    /// The quotient rounded toward negative infinity. The remainder has the sign of `rhs`.
+    pub fn div_rem_floor(&self, rhs: &Integer) -> (Integer, Integer) {
+        let (mut quotient, mut remainder) = self.div_rem(rhs);
+        if remainder.sign.is_some() && remainder.sign != rhs.sign {
+            quotient -= &Integer::ONE;
+            remainder += rhs;
+        }
+        (quotient, remainder)
+    }
+    pub fn div_floor(&self, rhs: &Integer) -> Integer {
+        self.div_rem_floor(rhs).0
+    }
+    pub fn mod_floor(&self, rhs: &Integer) -> Integer {
+        self.div_rem_floor(rhs).1
+    }
+    /// The quotient rounded toward positive infinity. The remainder is zero or has the
+    /// opposite sign of `rhs`.
+    pub fn div_rem_ceil(&self, rhs: &Integer) -> (Integer, Integer) {
+        let (mut quotient, mut remainder) = self.div_rem(rhs);
+        if remainder.sign.is_some() && remainder.sign == rhs.sign {
+            quotient += &Integer::ONE;
+            remainder -= rhs;
+        }
+        (quotient, remainder)
+    }
+    pub fn div_ceil(&self, rhs: &Integer) -> Integer {
+        self.div_rem_ceil(rhs).0
+    }
+    /// The quotient of Euclidean division, where the remainder is never negative, like
+    /// [i64::div_euclid] and [i64::rem_euclid].
+    pub fn div_rem_euclid(&self, rhs: &Integer) -> (Integer, Integer) {
+        let (mut quotient, mut remainder) = self.div_rem(rhs);
+        if remainder.is_negative() {
+            if rhs.is_negative() {
+                quotient += &Integer::ONE;
+                remainder -= rhs;
+            } else {
+                quotient -= &Integer::ONE;
+                remainder += rhs;
+            }
+        }
+        (quotient, remainder)
+    }
+    pub fn div_euclid(&self, rhs: &Integer) -> Integer {
+        self.div_rem_euclid(rhs).0
+    }
+    pub fn rem_euclid(&self, rhs: &Integer) -> Integer {
+        self.div_rem_euclid(rhs).1
+    }
+}
+
+impl Integer {
+    /// `self += rhs`, for `rhs` given by its sign and magnitude. Opposite signs subtract the
+    /// smaller magnitude from the larger one, which then decides the sign.
+    fn add_signed(&mut self, rhs_sign: Option<Sign>, rhs: &Natural) {
+        if self.signed() == rhs_sign.unwrap_or(Sign::Positive) {
+            self.magnitude += rhs;
+            self.sign = self.sign.or(rhs_sign);
+        } else if self.magnitude >= *rhs {
+            self.magnitude -= rhs;
+            self.trim_sign();
+        } else {
+            let mut magnitude = rhs.clone();
+            magnitude -= &self.magnitude;
+            *self = Integer {
+                sign: rhs_sign,
+                magnitude,
+            };
+        }
+    }
+}
+
+impl AddAssign<&Integer> for Integer {
+    fn add_assign(&mut self, rhs: &Integer) {
+        self.add_signed(rhs.sign, &rhs.magnitude);
+    }
+}
+
+impl SubAssign<&Integer> for Integer {
+    fn sub_assign(&mut self, rhs: &Integer) {
+        self.add_signed(rhs.sign.map(Neg::neg), &rhs.magnitude);
+    }
+}
+
+impl MulAssign<&Integer> for Integer {
+    fn mul_assign(&mut self, rhs: &Integer) {
+        self.sign = Some(self.signed() * rhs.signed());
+        self.magnitude *= &rhs.magnitude;
+        self.trim_sign();
+    }
+}
+
+/// Rounds toward zero, see [Integer::div_rem].
+impl DivAssign<&Integer> for Integer {
+    fn div_assign(&mut self, rhs: &Integer) {
+        *self = self.div_rem(rhs).0;
+    }
+}
+
+/// Has the sign of the dividend, see [Integer::div_rem].
+impl RemAssign<&Integer> for Integer {
+    fn rem_assign(&mut self, rhs: &Integer) {
+        *self = self.div_rem(rhs).1;
+    }
+}
+
+/// Derives the owned, mixed and borrowed operator impls from `$assign<&Integer> for Integer`.
+///
+/// Owned left hand sides are updated in place and returned, so their magnitude is reused.
+macro_rules! forward_binop {
+    ($($imp:ident $method:ident $assign:ident $assign_method:ident)*) => ($(
+        impl $imp<Integer> for Integer {
+            type Output = Integer;
+            fn $method(mut self, rhs: Integer) -> Integer {
+                self.$assign_method(&rhs);
+                self
+            }
+        }
+        impl $imp<&Integer> for Integer {
+            type Output = Integer;
+            fn $method(mut self, rhs: &Integer) -> Integer {
+                self.$assign_method(rhs);
+                self
+            }
+        }
+        impl $imp<Integer> for &Integer {
+            type Output = Integer;
+            fn $method(self, rhs: Integer) -> Integer {
+                self.clone().$method(&rhs)
+            }
+        }
+        impl $imp<&Integer> for &Integer {
+            type Output = Integer;
+            fn $method(self, rhs: &Integer) -> Integer {
+                self.clone().$method(rhs)
+            }
+        }
+        impl $assign<Integer> for Integer {
+            fn $assign_method(&mut self, rhs: Integer) {
+                self.$assign_method(&rhs);
+            }
+        }
+    )*)
+}
+
+forward_binop! {
+    Add add AddAssign add_assign
+    Sub sub SubAssign sub_assign
+    Mul mul MulAssign mul_assign
+    Div div DivAssign div_assign
+    Rem rem RemAssign rem_assign
+}
+
+impl Neg for Integer {
+    type Output = Integer;
+    fn neg(mut self) -> Integer {
+        self.sign = self.sign.map(Neg::neg);
+        self
+    }
+}
+
+impl Neg for &Integer {
+    type Output = Integer;
+    fn neg(self) -> Integer {
+        -self.clone()
+    }
+}
+
+/// Negative values compare by reversed magnitude, below zero and the positive values.
+impl Ord for Integer {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let rank = |sign| match sign {
+            Some(Sign::Negative) => -1,
+            None => 0,
+            Some(Sign::Positive) => 1,
+        };
+        rank(self.sign)
+            .cmp(&rank(other.sign))
+            .then_with(|| match self.sign {
+                Some(Sign::Negative) => other.magnitude.cmp(&self.magnitude),
+                _ => self.magnitude.cmp(&other.magnitude),
+            })
+    }
+}
+
+impl PartialOrd<Self> for Integer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Bitwise operations act on the two's complement, as if negative values had infinitely
+/// many leading one bits. A negative `-m` has the bits of `!(m - 1)`, so every operation
+/// below works on the finite `m - 1` and rewrites the result the same way.
+impl Integer {
+    /// Whether the infinitely many leading bits are ones, and the finite bits below them.
+    /// For negative values the finite bits are stored complemented.
+    fn twos_complement(&self) -> (bool, Natural) {
+        match self.sign {
+            Some(Sign::Negative) => {
+                let mut bits = self.magnitude.clone();
+                bits -= &Natural::ONE;
+                (true, bits)
+            }
+            _ => (false, self.magnitude.clone()),
+        }
+    }
+    /// The negative value whose two's complement is `!bits`.
+    fn from_complement(mut bits: Natural) -> Integer {
+        bits += &Natural::ONE;
+        Integer::from_sign_magnitude(Sign::Negative, bits)
+    }
+}
+
+/// `lhs & !rhs`.
+fn and_not(lhs: &Natural, rhs: &Natural) -> Natural {
+    let rhs = rhs.hunks().iter().chain(std::iter::repeat(&0));
+    lhs.hunks().iter().zip(rhs).map(|(l, r)| l & !r).collect()
+}
+
+impl BitAndAssign<&Integer> for Integer {
+    fn bitand_assign(&mut self, rhs: &Integer) {
+        *self = match (self.twos_complement(), rhs.twos_complement()) {
+            ((false, lhs), (false, rhs)) => Integer::from(lhs & rhs),
+            ((false, lhs), (true, rhs)) | ((true, rhs), (false, lhs)) => {
+                Integer::from(and_not(&lhs, &rhs))
+            }
+            ((true, lhs), (true, rhs)) => Integer::from_complement(lhs | rhs),
+        };
+    }
+}
+
+impl BitOrAssign<&Integer> for Integer {
+    fn bitor_assign(&mut self, rhs: &Integer) {
+        *self = match (self.twos_complement(), rhs.twos_complement()) {
+            ((false, lhs), (false, rhs)) => Integer::from(lhs | rhs),
+            ((false, lhs), (true, rhs)) | ((true, rhs), (false, lhs)) => {
+                Integer::from_complement(and_not(&rhs, &lhs))
+            }
+            ((true, lhs), (true, rhs)) => Integer::from_complement(lhs & rhs),
+        };
+    }
+}
+
+impl BitXorAssign<&Integer> for Integer {
+    fn bitxor_assign(&mut self, rhs: &Integer) {
+        *self = match (self.twos_complement(), rhs.twos_complement()) {
+            ((false, lhs), (false, rhs)) | ((true, lhs), (true, rhs)) => Integer::from(lhs ^ rhs),
+            ((false, lhs), (true, rhs)) | ((true, lhs), (false, rhs)) => {
+                Integer::from_complement(lhs ^ rhs)
+            }
+        };
+    }
+}
+
+forward_binop! {
+    BitAnd bitand BitAndAssign bitand_assign
+    BitOr bitor BitOrAssign bitor_assign
+    BitXor bitxor BitXorAssign bitxor_assign
+}
+
+/// `!self == -self - 1`, like for the signed primitives.
+impl Not for Integer {
+    type Output = Integer;
+    fn not(self) -> Integer {
+        -self - &Integer::ONE
+    }
+}
+
+impl Not for &Integer {
+    type Output = Integer;
+    fn not(self) -> Integer {
+        !self.clone()
+    }
+}
+
+/// Shifts by the same counts as [Natural]. Left shifts multiply by a power of two, right
+/// shifts divide by one and round toward negative infinity, like `>>` on the signed
+/// primitives.
+macro_rules! impl_shift {
+    ($($t:ty)*) => ($(
+        impl ShlAssign<$t> for Integer {
+            fn shl_assign(&mut self, rhs: $t) {
+                self.magnitude <<= rhs;
+            }
+        }
+        impl ShrAssign<$t> for Integer {
+            /// Negative values shift their complement `m - 1`, which floors the quotient.
+            fn shr_assign(&mut self, rhs: $t) {
+                if self.is_negative() {
+                    self.magnitude -= &Natural::ONE;
+                    self.magnitude >>= rhs;
+                    self.magnitude += &Natural::ONE;
+                } else {
+                    self.magnitude >>= rhs;
+                    self.trim_sign();
+                }
+            }
+        }
+        impl Shl<$t> for Integer {
+            type Output = Integer;
+            fn shl(mut self, rhs: $t) -> Integer {
+                self <<= rhs;
+                self
+            }
+        }
+        impl Shl<$t> for &Integer {
+            type Output = Integer;
+            fn shl(self, rhs: $t) -> Integer {
+                self.clone() << rhs
+            }
+        }
+        impl Shr<$t> for Integer {
+            type Output = Integer;
+            fn shr(mut self, rhs: $t) -> Integer {
+                self >>= rhs;
+                self
+            }
+        }
+        impl Shr<$t> for &Integer {
+            type Output = Integer;
+            fn shr(self, rhs: $t) -> Integer {
+                self.clone() >> rhs
+            }
+        }
+    )*)
+}
+
+impl_shift! { u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize Natural &Natural }
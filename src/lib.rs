@@ -1,24 +1,38 @@
 pub mod convert;
+pub mod error;
 pub mod fmt;
 pub mod integer;
 pub mod naturals;
 pub mod ops;
+pub mod rational;
 pub mod real;
 #[cfg(test)]
-#[cfg(target_pointer_width = "64")]
 mod tests;
 
-#[test]
-#[cfg(target_pointer_width = "32")]
-fn integer_from_u64() {
-    use crate::integer::Integer;
-    assert_eq!(Integer::new(u64::MIN).parts, vec![0usize, 0usize]);
-    assert_eq!(Integer::new(u64::MAX).parts, vec![usize::MAX, usize::MAX]);
-    assert_eq!(Integer::new(42u64).parts, vec![42usize, 0usize]);
-}
-
-#[derive(Eq, PartialEq, Ord, PartialOrd, Debug, Copy, Clone)]
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Copy, Clone)]
 pub enum Sign {
     Negative,
     Positive,
 }
+
+impl std::ops::Neg for Sign {
+    type Output = Sign;
+    fn neg(self) -> Sign {
+        match self {
+            Sign::Negative => Sign::Positive,
+            Sign::Positive => Sign::Negative,
+        }
+    }
+}
+
+/// The sign of a product.
+impl std::ops::Mul for Sign {
+    type Output = Sign;
+    fn mul(self, rhs: Sign) -> Sign {
+        if self == rhs {
+            Sign::Positive
+        } else {
+            Sign::Negative
+        }
+    }
+}
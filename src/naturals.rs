@@ -1,91 +1,136 @@
+pub(crate) mod arith;
+pub(crate) mod div;
+pub(crate) mod hunks;
 pub mod iter;
-use crate::naturals::iter::BitIter;
-use Natural::{Big, Small};
+pub(crate) mod mul;
+pub(crate) mod ntt;
+pub(crate) mod radix;
+use crate::naturals::hunks::Hunks;
+use crate::naturals::iter::{BitIter, Ones};
 use std::cmp::Ordering;
+use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::iter::{Product, Rev, Sum};
 
-#[derive(Debug, Clone)]
-pub enum Natural {
-    Small(usize),
-    Big(Vec<usize>),
+/// One digit of a [Natural] in base `2^64`. The width is the same on every target, so
+/// results, exported hunks and `Debug` output do not depend on the pointer width.
+pub type Hunk = u64;
+
+/// An arbitrarily large natural number, stored as hunks with the least significant first.
+///
+/// Values of up to four hunks live inline without an allocation, longer ones on the heap.
+/// The hunks never end in a zero hunk, so zero has no hunks at all.
+#[derive(Clone)]
+pub struct Natural {
+    hunks: Hunks,
 }
 impl Natural {
     pub fn new(n: impl Into<Natural>) -> Self {
         n.into()
     }
+    /// Whether `self` fits into a single hunk.
     pub fn is_small(&self) -> bool {
-        matches!(self, Small(_))
+        self.hunks.len() <= 1
     }
     pub fn is_big(&self) -> bool {
-        matches!(self, Big(_))
-    }
-    pub(crate) fn trim(self) -> Self {
-        if let Big(mut inner) = self {
-            while inner.pop_if(|x| 0usize.eq(x)).is_some() {}
-            match inner.len().cmp(&1) {
-                Ordering::Greater => Big(inner),
-                Ordering::Equal => {
-                    // This code should be safe because we just checked the bounds.
-                    // Unsafety could come from dereferencing.
-                    // Depending on if the pointed to usize gets copied or not.
-                    // If not then the pointed to memory is maybe unallocated in case inner drops.
-                    // This is just to make trim(...) faster.
-                    unsafe { Small(*inner.get_unchecked(0)) }
-                }
-                Ordering::Less => Small(0),
-            }
-        } else {
-            self
+        !self.is_small()
+    }
+    pub(crate) const fn from_hunk(hunk: Hunk) -> Self {
+        Natural {
+            hunks: Hunks::from_hunk(hunk),
+        }
+    }
+    /// The number with the given hunks, least significant first. Trailing zeros are dropped.
+    pub(crate) fn from_hunks(mut hunks: Vec<Hunk>) -> Self {
+        hunks.truncate(arith::trimmed(&hunks).len());
+        Natural {
+            hunks: Hunks::from_vec(hunks),
         }
     }
-    pub fn last_hunk(&self) -> usize {
-        match self {
-            Small(inner) => *inner,
-            Big(hunks) => *hunks.last().expect("Natural::Big(_) is never empty"),
+    /// Like [from_hunks](Natural::from_hunks), but only allocates if the value is too long
+    /// to be stored inline.
+    pub(crate) fn from_slice(hunks: &[Hunk]) -> Self {
+        Natural {
+            hunks: Hunks::from_slice(arith::trimmed(hunks)),
         }
     }
-    pub fn first_hunk(&self) -> usize {
-        match self {
-            Small(hunk) => *hunk,
-            Big(hunks) => *hunks.first().expect("Big(_) is never empty"),
+    /// The value of `self` if it fits into a single hunk.
+    pub(crate) fn as_hunk(&self) -> Option<Hunk> {
+        match *self.hunks {
+            [] => Some(0),
+            [hunk] => Some(hunk),
+            _ => None,
         }
     }
+    /// The significant hunks of `self`, least significant first.
+    ///
+    /// Trailing zero hunks are never part of the slice, so zero is the empty slice.
+    /// Equality, ordering and hashing all go through this view.
+    pub(crate) fn hunks(&self) -> &[Hunk] {
+        &self.hunks
+    }
+    /// The hunks of `self` as a growable buffer.
+    ///
+    /// The buffer is edited in place, so callers have to [trim_in_place](Natural::trim_in_place)
+    /// once they are done with it.
+    pub(crate) fn hunks_mut(&mut self) -> &mut Hunks {
+        &mut self.hunks
+    }
+    pub(crate) fn into_hunks(self) -> Vec<Hunk> {
+        self.hunks.into_vec()
+    }
+    pub(crate) fn trim_in_place(&mut self) {
+        self.hunks.trim();
+    }
+    pub fn last_hunk(&self) -> Hunk {
+        self.hunks.last().copied().unwrap_or(0)
+    }
+    pub fn first_hunk(&self) -> Hunk {
+        self.hunks.first().copied().unwrap_or(0)
+    }
+    /// The number of hunks, where zero counts as one hunk.
     #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
-        match self {
-            Small(_) => 1,
-            Big(hunks) => hunks.len(),
-        }
+        self.hunks.len().max(1)
     }
     pub const fn max_hunks() -> usize {
-        (isize::MAX >> size_of::<usize>().ilog2()).unsigned_abs()
+        isize::MAX.unsigned_abs() / size_of::<Hunk>()
     }
     #[allow(non_snake_case)]
     pub fn BITS(&self) -> Natural {
-        (Small(usize::BITS as usize) * Small(self.len())
-            - Natural::new(self.last_hunk().leading_zeros()))
-        .expect("This equation always equals 0 or higher")
+        Natural::new(self.bit_len())
     }
-    pub fn bits(&self) -> BitIter {
-        BitIter::from(self.clone())
+    /// The bits from the most significant one down. Zero has none.
+    pub fn bits(&self) -> BitIter<'_> {
+        BitIter::from(self)
+    }
+    /// The bits from bit 0 up to the most significant one.
+    pub fn bits_lsb(&self) -> Rev<BitIter<'_>> {
+        self.bits().rev()
+    }
+    /// The indices of the set bits, in increasing order.
+    pub fn ones(&self) -> Ones<'_> {
+        Ones::from(self)
+    }
+}
+
+impl Debug for Natural {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Natural").field(&self.hunks()).finish()
     }
 }
+
 /// Constants
 impl Natural {
-    pub const ZERO: Natural = Small(0);
-    pub const ONE: Natural = Small(1);
-    pub const TWO: Natural = Small(2);
-    pub const MAX_SMALL: Natural = Small(usize::MAX);
+    pub const ZERO: Natural = Natural::from_hunk(0);
+    pub const ONE: Natural = Natural::from_hunk(1);
+    pub const TWO: Natural = Natural::from_hunk(2);
+    pub const MAX_SMALL: Natural = Natural::from_hunk(Hunk::MAX);
 }
 
 impl PartialEq<Self> for Natural {
     fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (Small(lhs), Small(rhs)) => lhs.eq(rhs),
-            (Big(lhs), Big(rhs)) => {
-                lhs.iter().zip(rhs).all(|(l, r)| l == r) && lhs.len() == rhs.len()
-            }
-            _ => false,
-        }
+        self.hunks() == other.hunks()
     }
 }
 
@@ -93,69 +138,257 @@ impl Eq for Natural {}
 
 impl Ord for Natural {
     fn cmp(&self, other: &Self) -> Ordering {
-        match (self, other) {
-            (Small(lhs), Small(rhs)) => lhs.cmp(rhs),
-            (Big(_lhs), Big(_rhs)) => {
-                todo!()
-            }
-            (Small(_), Big(_)) => Ordering::Less,
-            (Big(_), Small(_)) => Ordering::Greater,
-        }
+        let (lhs, rhs) = (self.hunks(), other.hunks());
+        lhs.len()
+            .cmp(&rhs.len())
+            .then_with(|| lhs.iter().rev().cmp(rhs.iter().rev()))
     }
-    // fn max(self, _other: Self) -> Self
-    // where
-    //     Self: Sized,
-    // {
-    //     todo!()
-    // }
-    // fn min(self, _other: Self) -> Self
-    // where
-    //     Self: Sized,
-    // {
-    //     todo!()
-    // }
-    // fn clamp(self, _min: Self, _max: Self) -> Self
-    // where
-    //     Self: Sized,
-    // {
-    //     todo!()
-    // }
 }
 
 impl PartialOrd<Self> for Natural {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
-    // fn lt(&self, _other: &Self) -> bool {
-    //     todo!()
-    // }
-    // fn le(&self, _other: &Self) -> bool {
-    //     todo!()
-    // }
-    // fn gt(&self, _other: &Self) -> bool {
-    //     todo!()
-    // }
-    // fn ge(&self, _other: &Self) -> bool {
-    //     todo!()
-    // }
-}
-
-// impl Sum<Self> for Natural {
-//     fn sum<I: Iterator<Item = Self>>(_iter: I) -> Self {
-//         todo!()
-//     }
-// }
-
-// impl Product<Self> for Natural {
-//     fn product<I: Iterator<Item = Self>>(_iter: I) -> Self {
-//         todo!()
-//     }
-// }
-
-// impl Hash for Natural {
-//     fn hash<H: Hasher>(&self, _state: &mut H) {
-//         todo!()
-//     }
-// }
+}
+
+impl Hash for Natural {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.hunks().hash(state);
+    }
+}
+
+impl Sum<Natural> for Natural {
+    fn sum<I: Iterator<Item = Natural>>(iter: I) -> Self {
+        iter.fold(Natural::ZERO, |acc, n| acc + n)
+    }
+}
+
+impl<'a> Sum<&'a Natural> for Natural {
+    fn sum<I: Iterator<Item = &'a Natural>>(iter: I) -> Self {
+        iter.fold(Natural::ZERO, |acc, n| acc + n)
+    }
+}
+
+/// Multiplies neighbours pairwise until one factor is left.
+fn product_tree(mut factors: Vec<Natural>) -> Natural {
+    while factors.len() > 1 {
+        let mut pairs = factors.into_iter();
+        factors = std::iter::from_fn(|| {
+            let lhs = pairs.next()?;
+            Some(match pairs.next() {
+                Some(rhs) => lhs * rhs,
+                None => lhs,
+            })
+        })
+        .collect();
+    }
+    factors.pop().unwrap_or(Natural::ONE)
+}
+
+/// Multiplies neighbours pairwise until one factor is left, so that the operands of every
+/// multiplication have about the same size and the fast algorithms can kick in.
+impl Product<Natural> for Natural {
+    fn product<I: Iterator<Item = Natural>>(iter: I) -> Self {
+        let factors: Vec<Natural> = iter.collect();
+        if factors.contains(&Natural::ZERO) {
+            return Natural::ZERO;
+        }
+        product_tree(factors)
+    }
+}
+
+/// The first level of the tree multiplies the borrowed factors, so only an odd one out is
+/// cloned.
+impl<'a> Product<&'a Natural> for Natural {
+    fn product<I: Iterator<Item = &'a Natural>>(iter: I) -> Self {
+        let factors: Vec<&Natural> = iter.collect();
+        if factors.contains(&&Natural::ZERO) {
+            return Natural::ZERO;
+        }
+        let pairs = factors.chunks(2).map(|pair| match pair {
+            [lhs, rhs] => *lhs * *rhs,
+            _ => pair[0].clone(),
+        });
+        product_tree(pairs.collect())
+    }
+}
+
+/// Pseudo-random hunks for tests, from the xorshift state `seed`. Every other hunk or so is
+/// an edge value (zero, all ones, only the top bit, all ones but the lowest) to exercise
+/// carries and normalization. The top hunk is never zero, so the hunks are trimmed.
+#[cfg(test)]
+pub(crate) fn random_hunks(seed: &mut u64, len: usize) -> Vec<Hunk> {
+    let mut hunks: Vec<Hunk> = (0..len)
+        .map(|_| {
+            *seed ^= *seed << 13;
+            *seed ^= *seed >> 7;
+            *seed ^= *seed << 17;
+            match *seed % 8 {
+                0 => 0,
+                1 => Hunk::MAX,
+                2 => 1 << (Hunk::BITS - 1),
+                3 => Hunk::MAX - 1,
+                _ => *seed as Hunk,
+            }
+        })
+        .collect();
+    if let Some(top) = hunks.last_mut() {
+        *top |= 1;
+    }
+    hunks
+}
+
+#[cfg(test)]
+mod ord_tests {
+    use crate::naturals::Hunk;
+    use crate::naturals::Natural;
+    use std::collections::{BTreeMap, HashSet};
+    #[test]
+    fn cmp_big_big() {
+        assert!(Natural::from_hunks(vec![0, 1]) < Natural::from_hunks(vec![1, 1]));
+        assert!(Natural::from_hunks(vec![Hunk::MAX, 1]) < Natural::from_hunks(vec![0, 2]));
+        assert!(
+            Natural::from_hunks(vec![Hunk::MAX, Hunk::MAX]) < Natural::from_hunks(vec![0, 0, 1])
+        );
+        assert!(Natural::from_hunks(vec![3, 0, 1]) > Natural::from_hunks(vec![4, Hunk::MAX]));
+        assert_eq!(
+            Natural::from_hunks(vec![3, 4, 5]),
+            Natural::from_hunks(vec![3, 4, 5])
+        );
+    }
+    #[test]
+    fn cmp_small_big() {
+        assert!(Natural::from_hunk(Hunk::MAX) < Natural::from_hunks(vec![0, 1]));
+        assert!(Natural::from_hunks(vec![0, 1]) > Natural::from_hunk(Hunk::MAX));
+    }
+    #[test]
+    fn untrimmed_is_equal() {
+        assert_eq!(Natural::from_hunk(7), Natural::from_hunks(vec![7, 0, 0]));
+        assert_eq!(
+            Natural::from_hunks(vec![1, 2]),
+            Natural::from_hunks(vec![1, 2, 0])
+        );
+        assert_eq!(Natural::ZERO, Natural::from_hunks(vec![]));
+        let set: HashSet<Natural> = [
+            Natural::from_hunk(7),
+            Natural::from_hunks(vec![7, 0]),
+            Natural::from_hunks(vec![7]),
+        ]
+        .into();
+        assert_eq!(set.len(), 1);
+    }
+    #[test]
+    fn sort_and_btree() {
+        let mut values = vec![
+            Natural::from_hunks(vec![0, 0, 1]),
+            Natural::from_hunk(3),
+            Natural::from_hunks(vec![5, 1]),
+            Natural::from_hunks(vec![4, 1]),
+            Natural::from_hunk(0),
+        ];
+        values.sort();
+        assert_eq!(
+            values,
+            vec![
+                Natural::from_hunk(0),
+                Natural::from_hunk(3),
+                Natural::from_hunks(vec![4, 1]),
+                Natural::from_hunks(vec![5, 1]),
+                Natural::from_hunks(vec![0, 0, 1])
+            ]
+        );
+        let mut map = BTreeMap::new();
+        map.insert(Natural::from_hunks(vec![1, 1]), "a");
+        map.insert(Natural::from_hunks(vec![1, 1, 0]), "b");
+        assert_eq!(map.len(), 1);
+        assert_eq!(map[&Natural::from_hunks(vec![1, 1])], "b");
+    }
+}
+
+#[cfg(test)]
+mod sum_product_tests {
+    use crate::naturals::Natural;
+    #[test]
+    fn sum() {
+        let values: Vec<Natural> = (0..1000u32).map(Natural::new).collect();
+        assert_eq!(values.iter().sum::<Natural>(), Natural::from_hunk(499500));
+        let maxes = vec![Natural::MAX_SMALL; 4];
+        assert_eq!(
+            maxes.into_iter().sum::<Natural>(),
+            Natural::MAX_SMALL * Natural::from_hunk(4)
+        );
+        assert_eq!(
+            std::iter::empty::<Natural>().sum::<Natural>(),
+            Natural::ZERO
+        );
+    }
+    #[test]
+    fn product() {
+        let factorial = (1..=1000u32).map(Natural::new).product::<Natural>();
+        let sequential = (1..=1000u32).fold(Natural::ONE, |acc, n| acc * Natural::new(n));
+        assert_eq!(factorial, sequential);
+        assert_eq!(factorial.to_string().len(), 2568);
+        let values: Vec<Natural> = (1..=1000u32).map(Natural::new).collect();
+        assert_eq!(values.iter().product::<Natural>(), factorial);
+        assert_eq!(
+            values[..999].iter().product::<Natural>(),
+            factorial / &values[999]
+        );
+        let factors = [
+            Natural::from_hunk(3),
+            Natural::from_hunk(5),
+            Natural::from_hunk(7),
+        ];
+        assert_eq!(factors.iter().product::<Natural>(), Natural::from_hunk(105));
+        assert_eq!(
+            std::iter::empty::<Natural>().product::<Natural>(),
+            Natural::ONE
+        );
+        assert_eq!(
+            [Natural::from_hunk(2), Natural::ZERO, Natural::from_hunk(3)]
+                .into_iter()
+                .product::<Natural>(),
+            Natural::ZERO
+        );
+    }
+}
+
+#[cfg(test)]
+mod storage_tests {
+    use crate::naturals::Natural;
+    use crate::naturals::hunks::Hunks;
+
+    fn is_inline(n: &Natural) -> bool {
+        matches!(n.hunks, Hunks::Inline { .. })
+    }
+
+    #[test]
+    fn up_to_256_bits_stay_inline() {
+        let n = Natural::new(u128::MAX);
+        let square = &n * &n;
+        assert!(is_inline(&square));
+        assert_eq!(square.len(), 4);
+        assert!(is_inline(&(&square + &Natural::ONE)));
+        assert!(is_inline(&((&square >> 1u8) << 1u8)));
+        assert!(is_inline(&(&square >> 100u8)));
+        assert!(!is_inline(
+            &(square.clone() + square.clone() * Natural::MAX_SMALL)
+        ));
+        let huge = Natural::ONE << 1000u32;
+        assert!(!is_inline(&huge));
+        assert!(is_inline(&(huge >> 900u32).clone()));
+    }
+    #[test]
+    fn hunk_accessors() {
+        assert!(Natural::ZERO.is_small());
+        assert_eq!(Natural::ZERO.len(), 1);
+        assert_eq!(Natural::ZERO.first_hunk(), 0);
+        assert_eq!(Natural::ZERO.last_hunk(), 0);
+        let n = Natural::from_hunks(vec![1, 2, 3, 0]);
+        assert!(n.is_big());
+        assert_eq!((n.len(), n.first_hunk(), n.last_hunk()), (3, 1, 3));
+        assert_eq!(format!("{n:?}"), "Natural([1, 2, 3])");
+    }
+}
 
 // TODO add Traits from ops module
@@ -0,0 +1,129 @@
+//! Carry-propagating building blocks on the raw hunks of a [Natural](crate::naturals::Natural).
+//!
+//! All slices are least significant hunk first. Inputs may carry trailing zero hunks,
+//! outputs are not trimmed unless stated otherwise.
+
+use crate::naturals::Hunk;
+use std::cmp::Ordering;
+
+pub(crate) const HUNK_BITS: u32 = Hunk::BITS;
+
+/// `hunks` without its trailing zero hunks.
+pub(crate) fn trimmed(hunks: &[Hunk]) -> &[Hunk] {
+    let len = hunks
+        .iter()
+        .rposition(|hunk| *hunk != 0)
+        .map_or(0, |idx| idx + 1);
+    &hunks[..len]
+}
+
+/// Compares two hunk slices by value.
+pub(crate) fn cmp(lhs: &[Hunk], rhs: &[Hunk]) -> Ordering {
+    let (lhs, rhs) = (trimmed(lhs), trimmed(rhs));
+    lhs.len()
+        .cmp(&rhs.len())
+        .then_with(|| lhs.iter().rev().cmp(rhs.iter().rev()))
+}
+
+/// `lhs + rhs`.
+pub(crate) fn add(lhs: &[Hunk], rhs: &[Hunk]) -> Vec<Hunk> {
+    let (long, short) = if lhs.len() >= rhs.len() {
+        (lhs, rhs)
+    } else {
+        (rhs, lhs)
+    };
+    let mut sum = Vec::with_capacity(long.len() + 1);
+    sum.extend_from_slice(long);
+    sum.push(0);
+    add_assign(&mut sum, short);
+    sum
+}
+
+/// `acc += rhs`, returning the carry out of the top hunk of `acc`.
+///
+/// Trailing zero hunks of `rhs` may hang over the end of `acc`.
+pub(crate) fn add_assign(acc: &mut [Hunk], rhs: &[Hunk]) -> bool {
+    let rhs = trimmed(rhs);
+    assert!(rhs.len() <= acc.len(), "sum does not fit into its hunks");
+    let mut carry = false;
+    for (a, r) in acc.iter_mut().zip(rhs) {
+        let (sum, overflow0) = a.overflowing_add(*r);
+        let (sum, overflow1) = sum.overflowing_add(carry as Hunk);
+        *a = sum;
+        carry = overflow0 | overflow1;
+    }
+    for a in acc[rhs.len()..].iter_mut() {
+        if !carry {
+            break;
+        }
+        (*a, carry) = a.overflowing_add(1);
+    }
+    carry
+}
+
+/// `lhs - rhs`, which must not be negative.
+pub(crate) fn sub(lhs: &[Hunk], rhs: &[Hunk]) -> Vec<Hunk> {
+    let mut diff = lhs.to_vec();
+    let borrow = sub_assign(&mut diff, rhs);
+    debug_assert!(!borrow, "difference is negative");
+    diff
+}
+
+/// `acc -= rhs`, returning the borrow out of the top hunk of `acc`.
+///
+/// Trailing zero hunks of `rhs` may hang over the end of `acc`.
+pub(crate) fn sub_assign(acc: &mut [Hunk], rhs: &[Hunk]) -> bool {
+    let rhs = trimmed(rhs);
+    if rhs.len() > acc.len() {
+        return true;
+    }
+    let mut borrow = false;
+    for (a, r) in acc.iter_mut().zip(rhs) {
+        let (diff, overflow0) = a.overflowing_sub(*r);
+        let (diff, overflow1) = diff.overflowing_sub(borrow as Hunk);
+        *a = diff;
+        borrow = overflow0 | overflow1;
+    }
+    for a in acc[rhs.len()..].iter_mut() {
+        if !borrow {
+            break;
+        }
+        (*a, borrow) = a.overflowing_sub(1);
+    }
+    borrow
+}
+
+/// Shifts the hunks up by `shift < Hunk::BITS` bits, dropping what leaves the top hunk.
+pub(crate) fn shl_bits(hunks: &[Hunk], shift: u32) -> Vec<Hunk> {
+    if shift == 0 {
+        return hunks.to_vec();
+    }
+    let mut carry: Hunk = 0;
+    hunks
+        .iter()
+        .map(|hunk| {
+            let shifted = (hunk << shift) | carry;
+            carry = hunk >> (HUNK_BITS - shift);
+            shifted
+        })
+        .collect()
+}
+
+/// Shifts the hunks down by `shift < Hunk::BITS` bits.
+pub(crate) fn shr_bits(hunks: &[Hunk], shift: u32) -> Vec<Hunk> {
+    if shift == 0 {
+        return hunks.to_vec();
+    }
+    let mut carry: Hunk = 0;
+    let mut shifted: Vec<Hunk> = hunks
+        .iter()
+        .rev()
+        .map(|hunk| {
+            let shifted = (hunk >> shift) | carry;
+            carry = hunk << (HUNK_BITS - shift);
+            shifted
+        })
+        .collect();
+    shifted.reverse();
+    shifted
+}
@@ -0,0 +1,241 @@
+//! Long division on the raw hunks of a [Natural](crate::naturals::Natural).
+//!
+//! All slices are least significant hunk first. Divisors must not have trailing zero hunks.
+//! Short divisors use schoolbook division, long ones the recursive algorithm of Burnikel and
+//! Ziegler, which turns most of the work into multiplications and so profits from Karatsuba,
+//! Toom-3 and the NTT.
+
+use crate::naturals::Hunk;
+use crate::naturals::arith::{
+    HUNK_BITS, add, add_assign, cmp, shl_bits, shr_bits, sub_assign, trimmed,
+};
+use crate::naturals::mul::mul;
+use std::cmp::Ordering;
+
+/// Divisors and quotients shorter than this many hunks are divided with the schoolbook method.
+pub(crate) const BURNIKEL_ZIEGLER_THRESHOLD: usize = 60;
+
+/// Divides `lhs` by a single nonzero hunk and returns the quotient hunks and the remainder.
+pub(crate) fn div_rem_hunk(lhs: &[Hunk], rhs: Hunk) -> (Vec<Hunk>, Hunk) {
+    debug_assert_ne!(rhs, 0);
+    let rhs = rhs as u128;
+    let mut quotient = vec![0; lhs.len()];
+    let mut remainder = 0u128;
+    for (q, hunk) in quotient.iter_mut().zip(lhs).rev() {
+        let current = (remainder << HUNK_BITS) | *hunk as u128;
+        *q = (current / rhs) as Hunk;
+        remainder = current % rhs;
+    }
+    (quotient, remainder as Hunk)
+}
+
+/// Quotient and remainder of `lhs / rhs`.
+pub(crate) fn div_rem(lhs: &[Hunk], rhs: &[Hunk]) -> (Vec<Hunk>, Vec<Hunk>) {
+    let n = rhs.len();
+    assert!(n > 0 && rhs[n - 1] != 0, "division by zero");
+    if n < BURNIKEL_ZIEGLER_THRESHOLD || lhs.len() < n + BURNIKEL_ZIEGLER_THRESHOLD {
+        schoolbook(lhs, rhs)
+    } else {
+        burnikel_ziegler(lhs, rhs)
+    }
+}
+
+/// Schoolbook long division, Algorithm D from Knuth's TAOCP Vol. 2, 4.3.1.
+///
+/// Both operands are normalized so that the top bit of the divisor is set. That keeps every
+/// estimated quotient hunk at most two too large, which the correction steps then fix.
+fn schoolbook(lhs: &[Hunk], rhs: &[Hunk]) -> (Vec<Hunk>, Vec<Hunk>) {
+    let n = rhs.len();
+    if lhs.len() < n {
+        return (Vec::new(), lhs.to_vec());
+    }
+    if n == 1 {
+        let (quotient, remainder) = div_rem_hunk(lhs, rhs[0]);
+        return (quotient, vec![remainder]);
+    }
+    let shift = rhs[n - 1].leading_zeros();
+    let v = shl_bits(rhs, shift);
+    let mut u = shl_bits(lhs, shift);
+    u.push(if shift == 0 {
+        0
+    } else {
+        lhs[lhs.len() - 1] >> (HUNK_BITS - shift)
+    });
+    let m = lhs.len() - n;
+    let mut quotient = vec![0; m + 1];
+    let v_high = v[n - 1] as u128;
+    let v_next = v[n - 2] as u128;
+    for j in (0..=m).rev() {
+        let numerator = ((u[j + n] as u128) << HUNK_BITS) | u[j + n - 1] as u128;
+        let mut q_hat = if u[j + n] as u128 >= v_high {
+            Hunk::MAX as u128
+        } else {
+            numerator / v_high
+        };
+        let mut r_hat = numerator - q_hat * v_high;
+        while r_hat <= Hunk::MAX as u128
+            && q_hat * v_next > ((r_hat << HUNK_BITS) | u[j + n - 2] as u128)
+        {
+            q_hat -= 1;
+            r_hat += v_high;
+        }
+        if sub_mul(&mut u[j..=j + n], &v, q_hat as Hunk) {
+            // The estimate overshot by one, the carry out of adding back cancels the wrap.
+            q_hat -= 1;
+            add_assign(&mut u[j..=j + n], &v);
+        }
+        quotient[j] = q_hat as Hunk;
+    }
+    u.truncate(n);
+    (quotient, shr_bits(&u, shift))
+}
+
+/// Computes `acc -= rhs * factor` where `acc` has one hunk more than `rhs`.
+/// Returns `true` if the subtraction wrapped around.
+fn sub_mul(acc: &mut [Hunk], rhs: &[Hunk], factor: Hunk) -> bool {
+    let mut carry: Hunk = 0;
+    let mut borrow = false;
+    for (a, r) in acc.iter_mut().zip(rhs) {
+        let product = factor as u128 * *r as u128 + carry as u128;
+        carry = (product >> HUNK_BITS) as Hunk;
+        let (diff, overflow0) = a.overflowing_sub(product as Hunk);
+        let (diff, overflow1) = diff.overflowing_sub(borrow as Hunk);
+        *a = diff;
+        borrow = overflow0 | overflow1;
+    }
+    let top = &mut acc[rhs.len()];
+    let (diff, overflow0) = top.overflowing_sub(carry);
+    let (diff, overflow1) = diff.overflowing_sub(borrow as Hunk);
+    *top = diff;
+    overflow0 | overflow1
+}
+
+/// Recursive division from Burnikel and Ziegler, "Fast Recursive Division" (1998).
+///
+/// The divisor is padded with zero hunks and shifted until its length is `j · 2^k` with
+/// `j` below the threshold and its top bit is set. The dividend gets the same treatment,
+/// which leaves the quotient unchanged and scales the remainder. Then the dividend is
+/// consumed in blocks of the divisor length, each step dividing two blocks by the divisor.
+fn burnikel_ziegler(lhs: &[Hunk], rhs: &[Hunk]) -> (Vec<Hunk>, Vec<Hunk>) {
+    let mut k = 0;
+    while rhs.len() >> k >= BURNIKEL_ZIEGLER_THRESHOLD {
+        k += 1;
+    }
+    let n = rhs.len().div_ceil(1 << k) << k;
+    let pad = n - rhs.len();
+    let shift = rhs[rhs.len() - 1].leading_zeros();
+
+    let mut divisor = vec![0; pad];
+    divisor.extend(shl_bits(rhs, shift));
+    let mut dividend = vec![0; pad];
+    dividend.extend(shl_bits(lhs, shift));
+    dividend.push(if shift == 0 {
+        0
+    } else {
+        lhs[lhs.len() - 1] >> (HUNK_BITS - shift)
+    });
+    // At least one zero hunk on top, so the top block is smaller than the divisor.
+    let blocks = (trimmed(&dividend).len() / n + 1).max(2);
+    dividend.resize(blocks * n, 0);
+
+    let mut quotient = vec![0; (blocks - 1) * n];
+    let mut current = dividend[(blocks - 2) * n..].to_vec();
+    let mut remainder = Vec::new();
+    for idx in (0..blocks - 1).rev() {
+        let (q, r) = div_2n_1n(&current, &divisor, n);
+        quotient[idx * n..(idx + 1) * n].copy_from_slice(&q);
+        if idx == 0 {
+            remainder = r;
+        } else {
+            current = dividend[(idx - 1) * n..idx * n].to_vec();
+            current.extend(r);
+        }
+    }
+    let remainder = shr_bits(&remainder[pad..], shift);
+    (quotient, remainder)
+}
+
+/// Divides `lhs` of `2n` hunks by the normalized `rhs` of `n` hunks, given `lhs < rhs · 2^(n·Hunk::BITS)`.
+/// Both results have exactly `n` hunks.
+fn div_2n_1n(lhs: &[Hunk], rhs: &[Hunk], n: usize) -> (Vec<Hunk>, Vec<Hunk>) {
+    if n % 2 == 1 || n < BURNIKEL_ZIEGLER_THRESHOLD {
+        let (mut quotient, mut remainder) = schoolbook(lhs, rhs);
+        quotient.resize(n, 0);
+        remainder.resize(n, 0);
+        return (quotient, remainder);
+    }
+    let half = n / 2;
+    let (q_high, r) = div_3n_2n(&lhs[half..], rhs, half);
+    let mut next = lhs[..half].to_vec();
+    next.extend(r);
+    let (mut quotient, remainder) = div_3n_2n(&next, rhs, half);
+    quotient.extend(q_high);
+    (quotient, remainder)
+}
+
+/// Divides `lhs` of `3m` hunks by the normalized `rhs` of `2m` hunks, given
+/// `lhs < rhs · 2^(m·Hunk::BITS)`. Returns a quotient of `m` and a remainder of `2m` hunks.
+fn div_3n_2n(lhs: &[Hunk], rhs: &[Hunk], m: usize) -> (Vec<Hunk>, Vec<Hunk>) {
+    let (r_low, r_high) = rhs.split_at(m);
+    let (l_low, l_upper) = lhs.split_at(m);
+    // Estimate the quotient from the upper two thirds and the upper half of the divisor.
+    let (mut quotient, estimate_remainder) = if cmp(&l_upper[m..], r_high) == Ordering::Less {
+        div_2n_1n(l_upper, r_high, m)
+    } else {
+        // The quotient is β^m - 1 and l_upper - (β^m - 1) · r_high = l_upper - r_high · β^m + r_high.
+        let mut remainder = add(l_upper, r_high);
+        let borrow = sub_assign(&mut remainder[m..], r_high);
+        debug_assert!(!borrow);
+        (vec![Hunk::MAX; m], remainder)
+    };
+    let mut remainder = l_low.to_vec();
+    remainder.extend(estimate_remainder);
+    let correction = mul(&quotient, r_low);
+    // The estimate is at most two too large.
+    while cmp(&remainder, &correction) == Ordering::Less {
+        remainder.push(0);
+        add_assign(&mut remainder, rhs);
+        let borrow = sub_assign(&mut quotient, &[1]);
+        debug_assert!(!borrow);
+    }
+    let borrow = sub_assign(&mut remainder, &correction);
+    debug_assert!(!borrow);
+    remainder.resize(2 * m, 0);
+    (quotient, remainder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BURNIKEL_ZIEGLER_THRESHOLD, burnikel_ziegler, schoolbook};
+    use crate::naturals::arith::trimmed;
+    use crate::naturals::{Hunk, random_hunks};
+
+    #[test]
+    fn burnikel_ziegler_matches_schoolbook() {
+        let mut seed = 0x243F6A8885A308D3u64;
+        let t = BURNIKEL_ZIEGLER_THRESHOLD;
+        for (l, r) in [
+            (2 * t, t),
+            (3 * t + 5, t + 1),
+            (500, 130),
+            (1000, 499),
+            (1200, 240),
+            (777, 300),
+        ] {
+            let (lhs, rhs) = (random_hunks(&mut seed, l), random_hunks(&mut seed, r));
+            let (q0, r0) = schoolbook(&lhs, &rhs);
+            let (q1, r1) = burnikel_ziegler(&lhs, &rhs);
+            assert_eq!(trimmed(&q0), trimmed(&q1), "quotient of {l} / {r} hunks");
+            assert_eq!(trimmed(&r0), trimmed(&r1), "remainder of {l} / {r} hunks");
+        }
+    }
+    #[test]
+    fn burnikel_ziegler_all_ones() {
+        let lhs = vec![Hunk::MAX; 800];
+        let rhs = vec![Hunk::MAX; 250];
+        let (q0, r0) = schoolbook(&lhs, &rhs);
+        let (q1, r1) = burnikel_ziegler(&lhs, &rhs);
+        assert_eq!(trimmed(&q0), trimmed(&q1));
+        assert_eq!(trimmed(&r0), trimmed(&r1));
+    }
+}
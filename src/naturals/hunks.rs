@@ -0,0 +1,209 @@
+//! The hunk buffer of a [Natural](crate::naturals::Natural).
+//!
+//! Up to [INLINE_HUNKS] hunks are stored inline, longer values move to the heap. The buffer
+//! offers the few `Vec` operations the arithmetic needs and only allocates once the inline
+//! space runs out, so most values below `INLINE_HUNKS · Hunk::BITS` bits never allocate.
+
+use crate::naturals::Hunk;
+use std::ops::{Deref, DerefMut};
+
+/// The number of hunks that fit without an allocation.
+pub(crate) const INLINE_HUNKS: usize = 4;
+
+pub(crate) enum Hunks {
+    Inline { len: u8, buf: [Hunk; INLINE_HUNKS] },
+    Heap(Vec<Hunk>),
+}
+
+impl Hunks {
+    /// A single hunk, or no hunk at all for zero.
+    pub(crate) const fn from_hunk(hunk: Hunk) -> Self {
+        Hunks::Inline {
+            len: (hunk != 0) as u8,
+            buf: [hunk, 0, 0, 0],
+        }
+    }
+    pub(crate) fn from_slice(hunks: &[Hunk]) -> Self {
+        if hunks.len() <= INLINE_HUNKS {
+            let mut buf = [0; INLINE_HUNKS];
+            buf[..hunks.len()].copy_from_slice(hunks);
+            Hunks::Inline {
+                len: hunks.len() as u8,
+                buf,
+            }
+        } else {
+            Hunks::Heap(hunks.to_vec())
+        }
+    }
+    /// Keeps the allocation of `hunks` unless they fit inline.
+    pub(crate) fn from_vec(hunks: Vec<Hunk>) -> Self {
+        if hunks.len() <= INLINE_HUNKS {
+            Hunks::from_slice(&hunks)
+        } else {
+            Hunks::Heap(hunks)
+        }
+    }
+    pub(crate) fn into_vec(self) -> Vec<Hunk> {
+        match self {
+            Hunks::Inline { len, buf } => buf[..len as usize].to_vec(),
+            Hunks::Heap(hunks) => hunks,
+        }
+    }
+    /// Moves the hunks to the heap, with room for at least `additional` more.
+    fn spill(&mut self, additional: usize) -> &mut Vec<Hunk> {
+        if let Hunks::Inline { len, buf } = self {
+            let mut hunks = Vec::with_capacity((*len as usize + additional).max(2 * INLINE_HUNKS));
+            hunks.extend_from_slice(&buf[..*len as usize]);
+            *self = Hunks::Heap(hunks);
+        }
+        match self {
+            Hunks::Heap(hunks) => hunks,
+            Hunks::Inline { .. } => unreachable!("the hunks were just moved to the heap"),
+        }
+    }
+    pub(crate) fn push(&mut self, hunk: Hunk) {
+        match self {
+            Hunks::Inline { len, buf } if (*len as usize) < INLINE_HUNKS => {
+                buf[*len as usize] = hunk;
+                *len += 1;
+            }
+            _ => self.spill(1).push(hunk),
+        }
+    }
+    pub(crate) fn resize(&mut self, new_len: usize, value: Hunk) {
+        match self {
+            Hunks::Inline { len, buf } if new_len <= INLINE_HUNKS => {
+                if new_len > *len as usize {
+                    buf[*len as usize..new_len].fill(value);
+                }
+                *len = new_len as u8;
+            }
+            _ => {
+                let additional = new_len.saturating_sub(self.len());
+                self.spill(additional).resize(new_len, value);
+            }
+        }
+    }
+    pub(crate) fn truncate(&mut self, new_len: usize) {
+        match self {
+            Hunks::Inline { len, .. } => *len = new_len.min(*len as usize) as u8,
+            Hunks::Heap(hunks) => hunks.truncate(new_len),
+        }
+    }
+    /// Removes the lowest `count` hunks.
+    pub(crate) fn drain_front(&mut self, count: usize) {
+        match self {
+            Hunks::Inline { len, buf } => {
+                let count = count.min(*len as usize);
+                buf.copy_within(count..*len as usize, 0);
+                *len -= count as u8;
+            }
+            Hunks::Heap(hunks) => {
+                hunks.drain(..count.min(hunks.len()));
+            }
+        }
+    }
+    /// Inserts `count` zero hunks at the bottom.
+    pub(crate) fn insert_zeros_front(&mut self, count: usize) {
+        match self {
+            Hunks::Inline { len, buf } if *len as usize + count <= INLINE_HUNKS => {
+                buf.copy_within(..*len as usize, count);
+                buf[..count].fill(0);
+                *len += count as u8;
+            }
+            _ => {
+                let hunks = self.spill(count);
+                hunks.splice(0..0, std::iter::repeat_n(0, count));
+            }
+        }
+    }
+    /// Drops the trailing zero hunks.
+    pub(crate) fn trim(&mut self) {
+        let trimmed = self
+            .iter()
+            .rposition(|hunk| *hunk != 0)
+            .map_or(0, |idx| idx + 1);
+        self.truncate(trimmed);
+    }
+}
+
+impl Deref for Hunks {
+    type Target = [Hunk];
+    fn deref(&self) -> &[Hunk] {
+        match self {
+            Hunks::Inline { len, buf } => &buf[..*len as usize],
+            Hunks::Heap(hunks) => hunks,
+        }
+    }
+}
+
+impl DerefMut for Hunks {
+    fn deref_mut(&mut self) -> &mut [Hunk] {
+        match self {
+            Hunks::Inline { len, buf } => &mut buf[..*len as usize],
+            Hunks::Heap(hunks) => hunks,
+        }
+    }
+}
+
+/// Clones of short values are inline again, even if the original lives on the heap.
+impl Clone for Hunks {
+    fn clone(&self) -> Self {
+        Hunks::from_slice(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Hunk, Hunks, INLINE_HUNKS};
+
+    fn is_inline(hunks: &Hunks) -> bool {
+        matches!(hunks, Hunks::Inline { .. })
+    }
+
+    #[test]
+    fn grows_to_the_heap() {
+        let mut hunks = Hunks::from_hunk(1);
+        for hunk in 2..=INLINE_HUNKS {
+            hunks.push(hunk as Hunk);
+        }
+        assert!(is_inline(&hunks));
+        hunks.push(5);
+        assert!(!is_inline(&hunks));
+        assert_eq!(&hunks[..], [1, 2, 3, 4, 5]);
+        hunks.truncate(2);
+        let mut inline = hunks.clone();
+        assert!(is_inline(&inline));
+        inline.truncate(256);
+        assert_eq!(&inline[..], [1, 2]);
+    }
+    #[test]
+    fn front_edits() {
+        let mut hunks = Hunks::from_slice(&[1, 2]);
+        hunks.insert_zeros_front(2);
+        assert!(is_inline(&hunks));
+        assert_eq!(&hunks[..], [0, 0, 1, 2]);
+        hunks.drain_front(3);
+        assert_eq!(&hunks[..], [2]);
+        hunks.insert_zeros_front(INLINE_HUNKS);
+        assert!(!is_inline(&hunks));
+        assert_eq!(hunks.len(), INLINE_HUNKS + 1);
+        hunks.drain_front(10);
+        assert!(hunks.is_empty());
+    }
+    #[test]
+    fn resize_and_trim() {
+        let mut hunks = Hunks::from_hunk(0);
+        assert!(hunks.is_empty());
+        hunks.resize(3, 7);
+        assert_eq!(&hunks[..], [7, 7, 7]);
+        hunks[1] = 0;
+        hunks[2] = 0;
+        hunks.trim();
+        assert_eq!(&hunks[..], [7]);
+        hunks.resize(2 * INLINE_HUNKS, 0);
+        assert!(!is_inline(&hunks));
+        hunks.trim();
+        assert_eq!(hunks.into_vec(), vec![7]);
+    }
+}
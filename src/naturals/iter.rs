@@ -1,55 +1,145 @@
+use crate::naturals::Hunk;
 use crate::naturals::Natural;
-use crate::naturals::Natural::{Big, Small};
 
-pub struct BitIter {
-    hunks: Vec<usize>,
-    idx: Option<usize>,
-    mask: usize,
-}
-impl BitIter {
-    fn new(n: Natural) -> Self {
-        let hunks = match n.trim() {
-            Small(hunk) => vec![hunk],
-            Big(hunks) => hunks,
-        };
-        let idx = Some(hunks.len() - 1);
-        #[allow(clippy::unnecessary_literal_unwrap)]
-        let mask = 1usize << hunks[idx.unwrap()].ilog2();
-        Self { hunks, idx, mask }
-    }
-}
-impl From<Natural> for BitIter {
-    fn from(value: Natural) -> Self {
+/// The bits of a [Natural] from the most significant one down to bit 0. Iterating from the
+/// back, or [Natural::bits_lsb], goes the other way. Zero has no bits.
+#[derive(Debug, Clone)]
+pub struct BitIter<'a> {
+    hunks: &'a [Hunk],
+    /// The bits `low..high` are yet to be yielded.
+    low: usize,
+    high: usize,
+}
+impl<'a> BitIter<'a> {
+    fn new(n: &'a Natural) -> Self {
+        Self {
+            hunks: n.hunks(),
+            low: 0,
+            high: n.bit_len(),
+        }
+    }
+    fn bit(&self, idx: usize) -> bool {
+        self.hunks[idx / Hunk::BITS as usize] >> (idx % Hunk::BITS as usize) & 1 == 1
+    }
+}
+impl<'a> From<&'a Natural> for BitIter<'a> {
+    fn from(value: &'a Natural) -> Self {
         BitIter::new(value)
     }
 }
-impl Iterator for BitIter {
+impl Iterator for BitIter<'_> {
     type Item = bool;
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(idx) = self.idx {
-            let result = self.hunks[idx] & self.mask != 0;
-            self.mask = match self.mask {
-                1 => {
-                    self.idx = idx.checked_sub(1);
-                    isize::MAX.unsigned_abs() + 1
-                }
-                _ => self.mask >> 1,
-            };
-            Some(result)
-        } else {
-            None
+        if self.low == self.high {
+            return None;
+        }
+        self.high -= 1;
+        Some(self.bit(self.high))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
+}
+impl DoubleEndedIterator for BitIter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.low == self.high {
+            return None;
         }
+        self.low += 1;
+        Some(self.bit(self.low - 1))
+    }
+}
+impl ExactSizeIterator for BitIter<'_> {
+    fn len(&self) -> usize {
+        self.high - self.low
     }
 }
+
+/// The indices of the set bits of a [Natural], from bit 0 upwards.
+/// Runs of zeros are skipped a hunk at a time.
+#[derive(Debug, Clone)]
+pub struct Ones<'a> {
+    hunks: &'a [Hunk],
+    /// Index of the hunk in `current`.
+    idx: usize,
+    /// The bits of the current hunk that were not yielded yet.
+    current: Hunk,
+}
+impl<'a> Ones<'a> {
+    fn new(n: &'a Natural) -> Self {
+        let hunks = n.hunks();
+        Self {
+            hunks,
+            idx: 0,
+            current: hunks.first().copied().unwrap_or(0),
+        }
+    }
+}
+impl<'a> From<&'a Natural> for Ones<'a> {
+    fn from(value: &'a Natural) -> Self {
+        Ones::new(value)
+    }
+}
+impl Iterator for Ones<'_> {
+    type Item = usize;
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.current == 0 {
+            self.idx += 1;
+            self.current = *self.hunks.get(self.idx)?;
+        }
+        let bit = self.current.trailing_zeros() as usize;
+        self.current &= self.current - 1;
+        Some(self.idx * Hunk::BITS as usize + bit)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::naturals::Natural::Small;
+    use crate::naturals::Hunk;
+    use crate::naturals::Natural;
     use crate::naturals::iter::BitIter;
     #[test]
     fn test_bit_iter() {
-        let bits = BitIter::from(Small(100))
+        let bits = BitIter::from(&Natural::from_hunk(100))
             .map(|b| if b { 1 } else { 0 })
             .collect::<Vec<_>>();
         assert_eq!(bits, vec![1, 1, 0, 0, 1, 0, 0]);
     }
+    #[test]
+    fn both_ends() {
+        let n = Natural::from_hunk(100);
+        let lsb: Vec<bool> = n.bits_lsb().collect();
+        assert_eq!(lsb, [false, false, true, false, false, true, true]);
+        let mut bits = n.bits();
+        assert_eq!(bits.len(), 7);
+        assert_eq!(bits.next(), Some(true));
+        assert_eq!(bits.next_back(), Some(false));
+        assert_eq!(bits.len(), 5);
+        assert_eq!(
+            bits.rev().collect::<Vec<_>>(),
+            [false, true, false, false, true]
+        );
+        assert_eq!(Natural::ZERO.bits().len(), 0);
+        assert_eq!(Natural::ZERO.bits_lsb().next(), None);
+        assert_eq!(
+            Natural::from_hunks(vec![0, 1, 0]).bits().len(),
+            Hunk::BITS as usize + 1
+        );
+    }
+    #[test]
+    fn ones() {
+        let n = Natural::from_hunks(vec![0b1010, 0, 1 << 3]);
+        let hunk = Hunk::BITS as usize;
+        assert_eq!(n.ones().collect::<Vec<_>>(), [1, 3, 2 * hunk + 3]);
+        assert_eq!(Natural::ZERO.ones().next(), None);
+        assert_eq!(Natural::MAX_SMALL.ones().count(), hunk);
+        let n = Natural::new(0xdead_beef_u32) << 200u8;
+        let from_bits: Vec<usize> = n
+            .bits_lsb()
+            .enumerate()
+            .filter(|(_, b)| *b)
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(n.ones().collect::<Vec<_>>(), from_bits);
+    }
 }
@@ -0,0 +1,270 @@
+//! Multiplication on the raw hunks of a [Natural](crate::naturals::Natural).
+//!
+//! Small operands use the schoolbook kernel, larger ones recurse through Karatsuba and
+//! Toom-3, which split the operands and combine fewer, smaller products. Huge operands
+//! go through the number-theoretic transform in [ntt](crate::naturals::ntt).
+
+use crate::naturals::Hunk;
+use crate::naturals::arith::{
+    HUNK_BITS, add, add_assign, cmp, shl_bits, shr_bits, sub, sub_assign, trimmed,
+};
+use crate::naturals::div::div_rem_hunk;
+use crate::naturals::ntt;
+use std::cmp::Ordering;
+
+/// Below this many hunks in the shorter operand the schoolbook kernel is fastest.
+pub(crate) const KARATSUBA_THRESHOLD: usize = 40;
+/// From this many hunks in the shorter operand on Toom-3 beats Karatsuba.
+pub(crate) const TOOM3_THRESHOLD: usize = 240;
+/// From this many hunks in the shorter operand on the NTT beats Toom-3.
+pub(crate) const NTT_THRESHOLD: usize = 16000;
+
+/// `lhs * rhs`, untrimmed, with exactly `lhs.len() + rhs.len()` hunks.
+pub(crate) fn mul(lhs: &[Hunk], rhs: &[Hunk]) -> Vec<Hunk> {
+    let mut product = vec![0; lhs.len() + rhs.len()];
+    mul_into(&mut product, trimmed(lhs), trimmed(rhs));
+    product
+}
+
+/// Adds `lhs * rhs` onto `acc`, which has to be large enough to hold the sum.
+pub(crate) fn mul_into(acc: &mut [Hunk], lhs: &[Hunk], rhs: &[Hunk]) {
+    let (long, short) = if lhs.len() >= rhs.len() {
+        (lhs, rhs)
+    } else {
+        (rhs, lhs)
+    };
+    if short.is_empty() {
+        return;
+    }
+    if short.len() < KARATSUBA_THRESHOLD {
+        schoolbook(acc, long, short);
+    } else if long.len() >= 2 * short.len() {
+        // Unbalanced operands are cut into pieces of the shorter length first,
+        // so that the recursive algorithms always see roughly square inputs.
+        for (idx, chunk) in long.chunks(short.len()).enumerate() {
+            mul_into(&mut acc[idx * short.len()..], chunk, short);
+        }
+    } else if short.len() < TOOM3_THRESHOLD {
+        karatsuba(acc, long, short);
+    } else if short.len() < NTT_THRESHOLD {
+        toom3(acc, long, short);
+    } else {
+        add_assign(acc, &ntt::mul(long, short));
+    }
+}
+
+/// The classic `O(n·m)` kernel, one row of partial products per hunk of `rhs`.
+fn schoolbook(acc: &mut [Hunk], lhs: &[Hunk], rhs: &[Hunk]) {
+    for (offset, factor) in rhs.iter().enumerate() {
+        if *factor == 0 {
+            continue;
+        }
+        let mut carry: Hunk = 0;
+        for (a, l) in acc[offset..].iter_mut().zip(lhs) {
+            let sum = *factor as u128 * *l as u128 + *a as u128 + carry as u128;
+            *a = sum as Hunk;
+            carry = (sum >> HUNK_BITS) as Hunk;
+        }
+        add_assign(&mut acc[offset + lhs.len()..], &[carry]);
+    }
+}
+
+/// Splits `hunks` at `at`, or returns everything as the lower part if it is shorter.
+fn split(hunks: &[Hunk], at: usize) -> (&[Hunk], &[Hunk]) {
+    hunks.split_at(at.min(hunks.len()))
+}
+
+/// Karatsuba's method with `lhs = l1·x + l0` and `rhs = r1·x + r0`:
+/// `(l0 + l1)(r0 + r1) - l0·r0 - l1·r1` is the middle coefficient, three products instead of four.
+fn karatsuba(acc: &mut [Hunk], lhs: &[Hunk], rhs: &[Hunk]) {
+    let half = lhs.len().max(rhs.len()).div_ceil(2);
+    let (l0, l1) = split(lhs, half);
+    let (r0, r1) = split(rhs, half);
+    let low = mul(l0, r0);
+    let high = mul(l1, r1);
+    let mut middle = mul(&add(l0, l1), &add(r0, r1));
+    sub_assign_exact(&mut middle, &low);
+    sub_assign_exact(&mut middle, &high);
+    add_assign(acc, &low);
+    add_assign(&mut acc[half..], &middle);
+    add_assign(&mut acc[2 * half..], &high);
+}
+
+fn sub_assign_exact(acc: &mut [Hunk], rhs: &[Hunk]) {
+    let borrow = sub_assign(acc, rhs);
+    debug_assert!(!borrow, "intermediate product went negative");
+}
+
+/// A signed hunk vector, needed for the negative values Toom-3 interpolation passes through.
+#[derive(Clone)]
+struct Signed {
+    negative: bool,
+    magnitude: Vec<Hunk>,
+}
+
+impl Signed {
+    fn positive(magnitude: Vec<Hunk>) -> Self {
+        Signed {
+            negative: false,
+            magnitude,
+        }
+    }
+    fn add(self, rhs: Signed) -> Signed {
+        if self.negative == rhs.negative {
+            return Signed {
+                negative: self.negative,
+                magnitude: add(&self.magnitude, &rhs.magnitude),
+            };
+        }
+        match cmp(&self.magnitude, &rhs.magnitude) {
+            Ordering::Less => Signed {
+                negative: rhs.negative,
+                magnitude: sub(&rhs.magnitude, &self.magnitude),
+            },
+            _ => Signed {
+                negative: self.negative,
+                magnitude: sub(&self.magnitude, &rhs.magnitude),
+            },
+        }
+    }
+    fn sub(self, rhs: Signed) -> Signed {
+        self.add(Signed {
+            negative: !rhs.negative,
+            magnitude: rhs.magnitude,
+        })
+    }
+    fn shr1(self) -> Signed {
+        Signed {
+            negative: self.negative,
+            magnitude: shr_bits(&self.magnitude, 1),
+        }
+    }
+    fn div3(self) -> Signed {
+        let (magnitude, remainder) = div_rem_hunk(&self.magnitude, 3);
+        debug_assert_eq!(remainder, 0, "Toom-3 interpolation divides exactly");
+        Signed {
+            negative: self.negative,
+            magnitude,
+        }
+    }
+    fn into_magnitude(self) -> Vec<Hunk> {
+        debug_assert!(
+            !self.negative || trimmed(&self.magnitude).is_empty(),
+            "Toom-3 coefficients are never negative"
+        );
+        self.magnitude
+    }
+}
+
+/// Toom-Cook 3-way multiplication.
+///
+/// Both operands are read as polynomials of degree two in `x = 2^(k·Hunk::BITS)`, evaluated at
+/// `0, 1, -1, 2, ∞`, multiplied pointwise and interpolated with Bodrato's sequence.
+/// Five products of a third of the size replace the nine of the schoolbook method.
+fn toom3(acc: &mut [Hunk], lhs: &[Hunk], rhs: &[Hunk]) {
+    let k = lhs.len().max(rhs.len()).div_ceil(3);
+    let (l0, l) = split(lhs, k);
+    let (l1, l2) = split(l, k);
+    let (r0, r) = split(rhs, k);
+    let (r1, r2) = split(r, k);
+
+    let evaluate = |x0: &[Hunk], x1: &[Hunk], x2: &[Hunk]| {
+        let even = add(x0, x2);
+        let at_one = add(&even, x1);
+        let at_minus_one = Signed::positive(even).sub(Signed::positive(x1.to_vec()));
+        let mut at_two = add(x1, &shl_bits_grow(x2, 1));
+        at_two = add(x0, &shl_bits_grow(&at_two, 1));
+        (at_one, at_minus_one, at_two)
+    };
+    let (l_one, l_minus_one, l_two) = evaluate(l0, l1, l2);
+    let (r_one, r_minus_one, r_two) = evaluate(r0, r1, r2);
+
+    let p0 = mul(l0, r0);
+    let p1 = Signed::positive(mul(&l_one, &r_one));
+    let p_minus_one = Signed {
+        negative: l_minus_one.negative != r_minus_one.negative,
+        magnitude: mul(&l_minus_one.magnitude, &r_minus_one.magnitude),
+    };
+    let p2 = Signed::positive(mul(&l_two, &r_two));
+    let p_inf = mul(l2, r2);
+
+    let p_inf_signed = Signed::positive(p_inf.clone());
+    let mut r3 = p2.sub(p_minus_one.clone()).div3();
+    let r1 = p1.sub(p_minus_one.clone()).shr1();
+    let mut r2 = p_minus_one.sub(Signed::positive(p0.clone()));
+    r3 = r3
+        .sub(r2.clone())
+        .shr1()
+        .sub(r1.clone())
+        .sub(Signed::positive(shl_bits_grow(&p_inf, 1)));
+    r2 = r2.add(r1.clone()).sub(p_inf_signed);
+    let r1 = r1.sub(r3.clone());
+
+    add_assign(acc, &p0);
+    add_assign(&mut acc[k..], &r1.into_magnitude());
+    add_assign(&mut acc[2 * k..], &r2.into_magnitude());
+    add_assign(&mut acc[3 * k..], &r3.into_magnitude());
+    add_assign(&mut acc[4 * k..], &p_inf);
+}
+
+/// Like [shl_bits] but keeps the bits shifted out of the top hunk.
+fn shl_bits_grow(hunks: &[Hunk], shift: u32) -> Vec<Hunk> {
+    let mut shifted = shl_bits(hunks, shift);
+    shifted.push(hunks.last().map_or(0, |top| top >> (HUNK_BITS - shift)));
+    shifted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KARATSUBA_THRESHOLD, TOOM3_THRESHOLD, mul_into, schoolbook};
+    use crate::naturals::arith::trimmed;
+    use crate::naturals::{Hunk, random_hunks};
+
+    fn check(lhs: &[Hunk], rhs: &[Hunk]) {
+        let mut expected = vec![0; lhs.len() + rhs.len()];
+        schoolbook(&mut expected, lhs, rhs);
+        let mut actual = vec![0; lhs.len() + rhs.len()];
+        mul_into(&mut actual, trimmed(lhs), trimmed(rhs));
+        assert_eq!(expected, actual, "{} x {} hunks", lhs.len(), rhs.len());
+    }
+
+    #[test]
+    fn karatsuba_matches_schoolbook() {
+        let mut seed = 0x9E3779B97F4A7C15u64;
+        for (l, r) in [
+            (40, 40),
+            (41, 40),
+            (63, 45),
+            (100, 99),
+            (150, 90),
+            (90, 200),
+        ] {
+            assert!(l.min(r) >= KARATSUBA_THRESHOLD);
+            check(&random_hunks(&mut seed, l), &random_hunks(&mut seed, r));
+        }
+    }
+    #[test]
+    fn toom3_matches_schoolbook() {
+        let mut seed = 0xD1B54A32D192ED03u64;
+        for (l, r) in [(240, 240), (241, 250), (300, 260), (480, 479), (500, 1200)] {
+            assert!(l.min(r) >= TOOM3_THRESHOLD);
+            check(&random_hunks(&mut seed, l), &random_hunks(&mut seed, r));
+        }
+    }
+    #[test]
+    fn all_ones() {
+        let max = vec![Hunk::MAX; 400];
+        check(&max, &max);
+        check(&max[..200], &max);
+    }
+    #[test]
+    fn sparse_pieces() {
+        // Zero thirds make the evaluation at -1 negative and the pieces uneven.
+        let mut lhs = vec![0; 300];
+        lhs[250] = 7;
+        let mut rhs = vec![Hunk::MAX; 300];
+        rhs[100..200].fill(0);
+        check(&lhs, &rhs);
+        check(&rhs, &lhs);
+    }
+}
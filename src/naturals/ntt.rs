@@ -0,0 +1,223 @@
+//! Multiplication of huge hunk slices with number-theoretic transforms.
+//!
+//! Every hunk becomes one coefficient of a polynomial. The cyclic convolution of the two
+//! coefficient vectors is computed modulo three primes below `2^62` with a radix-2 NTT and
+//! then reconstructed with the Chinese remainder theorem. A coefficient of the exact
+//! convolution is smaller than `len · 2^128`, far below the product of the primes (`2^185`),
+//! so the reconstruction is exact for any input that fits into memory.
+//!
+//! Only 64-bit integer arithmetic with 128-bit products is used, no SIMD or floating point.
+
+use crate::naturals::Hunk;
+/// A prime of the form `c · 2^40 + 1` with Montgomery constants for `R = 2^64`.
+struct Prime {
+    p: u64,
+    /// `-p^-1 mod 2^64`
+    p_inv_neg: u64,
+    /// `R^2 mod p`, maps plain values into Montgomery form.
+    r2: u64,
+    /// A generator of the multiplicative group.
+    generator: u64,
+}
+
+impl Prime {
+    const fn new(p: u64, generator: u64) -> Self {
+        // Newton iteration for the inverse of p modulo 2^64, each step doubles the valid bits.
+        let mut inv = p;
+        let mut i = 0;
+        while i < 5 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(p.wrapping_mul(inv)));
+            i += 1;
+        }
+        let r = ((1u128 << 64) % p as u128) as u64;
+        let r2 = ((r as u128 * r as u128) % p as u128) as u64;
+        Prime {
+            p,
+            p_inv_neg: inv.wrapping_neg(),
+            r2,
+            generator,
+        }
+    }
+    /// Montgomery product `a · b · R^-1 mod p` for `a, b < p`.
+    #[inline]
+    fn mul(&self, a: u64, b: u64) -> u64 {
+        let t = a as u128 * b as u128;
+        let m = (t as u64).wrapping_mul(self.p_inv_neg);
+        let u = ((t + m as u128 * self.p as u128) >> 64) as u64;
+        if u >= self.p { u - self.p } else { u }
+    }
+    #[inline]
+    fn add(&self, a: u64, b: u64) -> u64 {
+        let sum = a + b;
+        if sum >= self.p { sum - self.p } else { sum }
+    }
+    #[inline]
+    fn sub(&self, a: u64, b: u64) -> u64 {
+        if a >= b { a - b } else { a + self.p - b }
+    }
+    fn to_montgomery(&self, a: u64) -> u64 {
+        self.mul(a % self.p, self.r2)
+    }
+    /// `base^exp` with base and result in Montgomery form.
+    fn pow(&self, mut base: u64, mut exp: u64) -> u64 {
+        let mut result = self.to_montgomery(1);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = self.mul(result, base);
+            }
+            base = self.mul(base, base);
+            exp >>= 1;
+        }
+        result
+    }
+    /// The powers `w^0, …, w^(len/2 - 1)` of a primitive `len`-th root of unity
+    /// (or of its inverse), in Montgomery form.
+    fn roots(&self, len: usize, inverse: bool) -> Vec<u64> {
+        let mut w = self.pow(
+            self.to_montgomery(self.generator),
+            (self.p - 1) / len as u64,
+        );
+        if inverse {
+            w = self.pow(w, len as u64 - 1);
+        }
+        let mut roots = Vec::with_capacity(len / 2);
+        let mut current = self.to_montgomery(1);
+        for _ in 0..len / 2 {
+            roots.push(current);
+            current = self.mul(current, w);
+        }
+        roots
+    }
+    /// Decimation-in-frequency transform, natural order in, bit-reversed order out.
+    fn forward(&self, values: &mut [u64], roots: &[u64]) {
+        let len = values.len();
+        let mut half = len / 2;
+        while half > 0 {
+            let stride = len / (2 * half);
+            for block in values.chunks_exact_mut(2 * half) {
+                let (low, high) = block.split_at_mut(half);
+                for (j, (a, b)) in low.iter_mut().zip(high.iter_mut()).enumerate() {
+                    let (x, y) = (*a, *b);
+                    *a = self.add(x, y);
+                    *b = self.mul(self.sub(x, y), roots[j * stride]);
+                }
+            }
+            half /= 2;
+        }
+    }
+    /// Decimation-in-time transform, bit-reversed order in, natural order out.
+    /// The result still has to be divided by the length.
+    fn inverse(&self, values: &mut [u64], roots: &[u64]) {
+        let len = values.len();
+        let mut half = 1;
+        while half < len {
+            let stride = len / (2 * half);
+            for block in values.chunks_exact_mut(2 * half) {
+                let (low, high) = block.split_at_mut(half);
+                for (j, (a, b)) in low.iter_mut().zip(high.iter_mut()).enumerate() {
+                    let (x, y) = (*a, self.mul(*b, roots[j * stride]));
+                    *a = self.add(x, y);
+                    *b = self.sub(x, y);
+                }
+            }
+            half *= 2;
+        }
+    }
+    /// The convolution of `lhs` and `rhs` modulo `p` as plain values, `len` coefficients long.
+    fn convolve(&self, lhs: &[Hunk], rhs: &[Hunk], len: usize) -> Vec<u64> {
+        let load = |hunks: &[Hunk]| {
+            let mut values: Vec<u64> = hunks.iter().map(|hunk| self.to_montgomery(*hunk)).collect();
+            values.resize(len, 0);
+            values
+        };
+        let roots = self.roots(len, false);
+        let mut lhs = load(lhs);
+        self.forward(&mut lhs, &roots);
+        let mut rhs = load(rhs);
+        self.forward(&mut rhs, &roots);
+        for (l, r) in lhs.iter_mut().zip(&rhs) {
+            *l = self.mul(*l, *r);
+        }
+        drop(rhs);
+        self.inverse(&mut lhs, &self.roots(len, true));
+        // A Montgomery product with the plain inverse of len leaves Montgomery form and scales.
+        let len_inv = self.pow(self.to_montgomery(len as u64), self.p - 2);
+        let len_inv = self.mul(len_inv, 1);
+        for value in lhs.iter_mut() {
+            *value = self.mul(*value, len_inv);
+        }
+        lhs
+    }
+}
+
+const PRIMES: [Prime; 3] = [
+    Prime::new(0x3fff810000000001, 5),
+    Prime::new(0x3fff6d0000000001, 3),
+    Prime::new(0x3fff450000000001, 10),
+];
+
+/// `lhs * rhs` with exactly `lhs.len() + rhs.len()` hunks.
+pub(crate) fn mul(lhs: &[Hunk], rhs: &[Hunk]) -> Vec<Hunk> {
+    let product_len = lhs.len() + rhs.len();
+    if lhs.is_empty() || rhs.is_empty() {
+        return vec![0; product_len];
+    }
+    let len = (product_len - 1).next_power_of_two();
+    assert!(len <= 1 << 40, "operands too large for the NTT");
+    let [p0, p1, p2] = &PRIMES;
+    let r0 = p0.convolve(lhs, rhs, len);
+    let r1 = p1.convolve(lhs, rhs, len);
+    let r2 = p2.convolve(lhs, rhs, len);
+
+    // Garner's algorithm, with the constants in Montgomery form so that one Montgomery
+    // product both multiplies and reduces.
+    let p0_inv_mod_p1 = p1.pow(p1.to_montgomery(p0.p), p1.p - 2);
+    let p01 = p0.p as u128 * p1.p as u128;
+    let p01_mod_p2 = (p01 % p2.p as u128) as u64;
+    let p01_inv_mod_p2 = p2.pow(p2.to_montgomery(p01_mod_p2), p2.p - 2);
+
+    let mut product = Vec::with_capacity(product_len);
+    let (mut carry_low, mut carry_high) = (0u64, 0u128);
+    for idx in 0..product_len {
+        let (mut low, mut high) = (carry_low, carry_high);
+        if idx < len {
+            let (a0, a1, a2) = (r0[idx], r1[idx], r2[idx]);
+            let t1 = p1.mul(p1.sub(a1, a0 % p1.p), p0_inv_mod_p1);
+            let x01 = a0 as u128 + p0.p as u128 * t1 as u128;
+            let x01_mod_p2 = (x01 % p2.p as u128) as u64;
+            let t2 = p2.mul(p2.sub(a2, x01_mod_p2), p01_inv_mod_p2);
+            // x = x01 + p01 · t2 as a 192-bit number (x_low + 2^64 · x_high).
+            let p01_t2_low = (p01 as u64) as u128 * t2 as u128;
+            let p01_t2_high = (p01 >> 64) * t2 as u128 + (p01_t2_low >> 64);
+            let x_low = p01_t2_low as u64;
+            let (x_low, c0) = x_low.overflowing_add(x01 as u64);
+            let x_high = p01_t2_high + (x01 >> 64) + c0 as u128;
+            let (sum, c1) = low.overflowing_add(x_low);
+            low = sum;
+            high += x_high + c1 as u128;
+        }
+        product.push(low);
+        carry_low = high as u64;
+        carry_high = high >> 64;
+    }
+    product
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::naturals::{Hunk, mul, random_hunks};
+
+    #[test]
+    fn matches_toom3() {
+        let mut seed = 0x853C49E6748FEA9Bu64;
+        for (l, r) in [(1, 1), (3, 700), (600, 600), (1023, 1025), (2000, 1500)] {
+            let (lhs, rhs) = (random_hunks(&mut seed, l), random_hunks(&mut seed, r));
+            assert_eq!(super::mul(&lhs, &rhs), mul::mul(&lhs, &rhs), "{l} x {r}");
+        }
+    }
+    #[test]
+    fn all_ones() {
+        let max = vec![Hunk::MAX; 3000];
+        assert_eq!(super::mul(&max, &max), mul::mul(&max, &max));
+    }
+}
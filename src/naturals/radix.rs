@@ -0,0 +1,304 @@
+//! Conversion between the raw hunks of a [Natural](crate::naturals::Natural) and digits.
+//!
+//! Power of two radices map bits to digits directly. All other radices work on chunks, the
+//! largest power of the radix that fits into a hunk. Long numbers are split recursively at
+//! `chunk^(2^k)` from a [PowerTree], so that the work goes into a few large divisions or
+//! multiplications instead of one pass per chunk over the whole number.
+
+use crate::naturals::Hunk;
+use crate::naturals::arith::{add_assign, trimmed};
+use crate::naturals::div::{div_rem, div_rem_hunk};
+use crate::naturals::mul::mul;
+
+/// Below this many hunks the chunk by chunk conversion is faster than splitting.
+pub(crate) const DIVIDE_AND_CONQUER_THRESHOLD: usize = 40;
+
+const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// The largest power of `radix` that fits into a hunk, together with its exponent.
+pub(crate) fn chunk_base(radix: u32) -> (Hunk, usize) {
+    let radix = Hunk::from(radix);
+    let (mut base, mut exponent) = (radix, 1);
+    while let Some(next) = base.checked_mul(radix) {
+        base = next;
+        exponent += 1;
+    }
+    (base, exponent)
+}
+
+/// The digits of `hunks` in `radix`, most significant first, as lower case ASCII.
+/// Zero is written as a single `0`.
+pub(crate) fn to_digits(hunks: &[Hunk], radix: u32) -> String {
+    assert!((2..=36).contains(&radix), "radix must be in 2..=36");
+    let hunks = trimmed(hunks);
+    let digits = if hunks.is_empty() {
+        vec![b'0']
+    } else if radix.is_power_of_two() {
+        to_digits_pow2(hunks, radix.trailing_zeros() as usize)
+    } else if hunks.len() < DIVIDE_AND_CONQUER_THRESHOLD {
+        to_digits_chunked(hunks, radix)
+    } else {
+        let mut tree = PowerTree::new(radix);
+        let mut level = 0;
+        while tree.power(level).len() <= hunks.len() {
+            level += 1;
+        }
+        let mut digits = Vec::new();
+        to_digits_recursive(&mut digits, hunks, &mut tree, level, false);
+        digits
+    };
+    String::from_utf8(digits).expect("digits are ASCII")
+}
+
+/// Reads `bits_per_digit` bits at a time, straight from the hunks.
+fn to_digits_pow2(hunks: &[Hunk], bits_per_digit: usize) -> Vec<u8> {
+    let hunk_bits = Hunk::BITS as usize;
+    let bit_len = hunks.len() * hunk_bits - hunks[hunks.len() - 1].leading_zeros() as usize;
+    let mask: Hunk = (1 << bits_per_digit) - 1;
+    (0..bit_len.div_ceil(bits_per_digit))
+        .rev()
+        .map(|digit| {
+            let (idx, offset) = (
+                (digit * bits_per_digit) / hunk_bits,
+                (digit * bits_per_digit) % hunk_bits,
+            );
+            let mut value = hunks[idx] >> offset;
+            if offset + bits_per_digit > hunk_bits && idx + 1 < hunks.len() {
+                value |= hunks[idx + 1] << (hunk_bits - offset);
+            }
+            DIGITS[(value & mask) as usize]
+        })
+        .collect()
+}
+
+/// Splits off as many digits as fit into a hunk per division.
+fn to_digits_chunked(hunks: &[Hunk], radix: u32) -> Vec<u8> {
+    let (base, digits_per_chunk) = chunk_base(radix);
+    let mut chunks = Vec::new();
+    let mut remaining = hunks.to_vec();
+    while !remaining.is_empty() {
+        let (quotient, chunk) = div_rem_hunk(&remaining, base);
+        chunks.push(chunk);
+        remaining = trimmed(&quotient).to_vec();
+    }
+    let mut digits = Vec::with_capacity(chunks.len() * digits_per_chunk);
+    for chunk in chunks.iter().rev() {
+        push_chunk(&mut digits, *chunk, radix, digits_per_chunk);
+    }
+    let leading_zeros = digits.iter().take_while(|digit| **digit == b'0').count();
+    digits.drain(..leading_zeros.min(digits.len() - 1));
+    digits
+}
+
+/// Appends the digits of `hunks < chunk^(2^level)`. With `pad` set, exactly
+/// `digits_per_chunk · 2^level` of them, otherwise without leading zeros.
+fn to_digits_recursive(
+    digits: &mut Vec<u8>,
+    hunks: &[Hunk],
+    tree: &mut PowerTree,
+    level: usize,
+    pad: bool,
+) {
+    let hunks = trimmed(hunks);
+    if hunks.len() < DIVIDE_AND_CONQUER_THRESHOLD || level == 0 {
+        let width = tree.digits_per_chunk << level;
+        if hunks.is_empty() {
+            digits.resize(digits.len() + if pad { width } else { 0 }, b'0');
+            return;
+        }
+        let chunked = to_digits_chunked(hunks, tree.radix);
+        if pad {
+            digits.resize(digits.len() + width - chunked.len(), b'0');
+        }
+        digits.extend(chunked);
+        return;
+    }
+    let (high, low) = div_rem(hunks, tree.power(level - 1));
+    if !pad && trimmed(&high).is_empty() {
+        to_digits_recursive(digits, &low, tree, level - 1, false);
+    } else {
+        to_digits_recursive(digits, &high, tree, level - 1, pad);
+        to_digits_recursive(digits, &low, tree, level - 1, true);
+    }
+}
+
+/// The hunks of the number with the given digit values, most significant first.
+/// Every value has to be below `radix`.
+pub(crate) fn from_digit_values(values: &[u8], radix: u32) -> Vec<Hunk> {
+    debug_assert!(values.iter().all(|value| u32::from(*value) < radix));
+    if radix.is_power_of_two() {
+        return from_digit_values_pow2(values, radix.trailing_zeros() as usize);
+    }
+    let digits_per_chunk = chunk_base(radix).1;
+    if values.len() < DIVIDE_AND_CONQUER_THRESHOLD * digits_per_chunk {
+        return from_digit_values_chunked(values, radix);
+    }
+    let mut tree = PowerTree::new(radix);
+    let mut level = 0;
+    while digits_per_chunk << (level + 1) < values.len() {
+        level += 1;
+    }
+    from_digit_values_recursive(values, &mut tree, level)
+}
+
+/// Packs `bits_per_digit` bits per digit, starting at the least significant one.
+fn from_digit_values_pow2(values: &[u8], bits_per_digit: usize) -> Vec<Hunk> {
+    let hunk_bits = Hunk::BITS as usize;
+    let mut hunks = vec![0; (values.len() * bits_per_digit).div_ceil(hunk_bits)];
+    for (digit, value) in values.iter().rev().enumerate() {
+        let (idx, offset) = (
+            (digit * bits_per_digit) / hunk_bits,
+            (digit * bits_per_digit) % hunk_bits,
+        );
+        hunks[idx] |= Hunk::from(*value) << offset;
+        if offset + bits_per_digit > hunk_bits {
+            hunks[idx + 1] |= Hunk::from(*value) >> (hunk_bits - offset);
+        }
+    }
+    hunks
+}
+
+/// Horner's method over chunks: multiply by the chunk base, add the next chunk.
+fn from_digit_values_chunked(values: &[u8], radix: u32) -> Vec<Hunk> {
+    let (base, digits_per_chunk) = chunk_base(radix);
+    let mut hunks: Vec<Hunk> = Vec::new();
+    let first = values.len() % digits_per_chunk;
+    let chunks = std::iter::once(&values[..first]).chain(values[first..].chunks(digits_per_chunk));
+    for chunk in chunks.filter(|chunk| !chunk.is_empty()) {
+        let value = chunk
+            .iter()
+            .fold(0, |acc, value| acc * Hunk::from(radix) + Hunk::from(*value));
+        let factor = base_for(chunk.len(), radix, base, digits_per_chunk);
+        let mut carry = value;
+        for hunk in hunks.iter_mut() {
+            let product = *hunk as u128 * factor as u128 + carry as u128;
+            *hunk = product as Hunk;
+            carry = (product >> Hunk::BITS) as Hunk;
+        }
+        if carry != 0 {
+            hunks.push(carry);
+        }
+    }
+    hunks
+}
+
+/// `radix^len`, which is the chunk base for full chunks.
+fn base_for(len: usize, radix: u32, base: Hunk, digits_per_chunk: usize) -> Hunk {
+    if len == digits_per_chunk {
+        base
+    } else {
+        Hunk::from(radix).pow(len as u32)
+    }
+}
+
+/// Parses `values`, which has at most `digits_per_chunk · 2^(level + 1)` digits,
+/// as `high · chunk^(2^level) + low`.
+fn from_digit_values_recursive(values: &[u8], tree: &mut PowerTree, level: usize) -> Vec<Hunk> {
+    let digits_per_chunk = tree.digits_per_chunk;
+    if values.len() < DIVIDE_AND_CONQUER_THRESHOLD * digits_per_chunk {
+        return from_digit_values_chunked(values, tree.radix);
+    }
+    let split = values.len().saturating_sub(digits_per_chunk << level);
+    let (high, low) = values.split_at(split);
+    let low = from_digit_values_recursive(low, tree, level.saturating_sub(1));
+    if high.is_empty() {
+        return low;
+    }
+    let high = from_digit_values_recursive(high, tree, level.saturating_sub(1));
+    let mut hunks = mul(&high, tree.power(level));
+    add_assign(&mut hunks, &low);
+    hunks
+}
+
+/// The powers `chunk^(2^k)` of the chunk base of a radix, each computed once per conversion
+/// by squaring the one before.
+pub(crate) struct PowerTree {
+    radix: u32,
+    digits_per_chunk: usize,
+    powers: Vec<Vec<Hunk>>,
+}
+
+impl PowerTree {
+    pub(crate) fn new(radix: u32) -> Self {
+        let (base, digits_per_chunk) = chunk_base(radix);
+        PowerTree {
+            radix,
+            digits_per_chunk,
+            powers: vec![vec![base]],
+        }
+    }
+    /// `chunk^(2^level)`
+    pub(crate) fn power(&mut self, level: usize) -> &[Hunk] {
+        while self.powers.len() <= level {
+            let last = &self.powers[self.powers.len() - 1];
+            let square = trimmed(&mul(last, last)).to_vec();
+            self.powers.push(square);
+        }
+        &self.powers[level]
+    }
+}
+
+/// Appends exactly `width` digits of `chunk`, padded with leading zeros.
+fn push_chunk(digits: &mut Vec<u8>, mut chunk: Hunk, radix: u32, width: usize) {
+    let start = digits.len();
+    digits.resize(start + width, b'0');
+    for digit in digits[start..].iter_mut().rev() {
+        *digit = DIGITS[(chunk % Hunk::from(radix)) as usize];
+        chunk /= Hunk::from(radix);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_digit_values, from_digit_values_chunked, to_digits, to_digits_chunked};
+    use crate::naturals::arith::trimmed;
+    use crate::naturals::random_hunks;
+
+    #[test]
+    fn divide_and_conquer_matches_chunked() {
+        let mut seed = 0x9E3779B97F4A7C15u64;
+        for len in [40, 41, 100, 333, 1000] {
+            let hunks = random_hunks(&mut seed, len);
+            for radix in [3, 10, 36] {
+                let digits = to_digits(&hunks, radix);
+                assert_eq!(
+                    digits.as_bytes(),
+                    to_digits_chunked(&hunks, radix),
+                    "{len} hunks in radix {radix}"
+                );
+                let values: Vec<u8> = digits
+                    .bytes()
+                    .map(|digit| char::from(digit).to_digit(36).unwrap() as u8)
+                    .collect();
+                assert_eq!(
+                    trimmed(&from_digit_values(&values, radix)),
+                    trimmed(&from_digit_values_chunked(&values, radix))
+                );
+                assert_eq!(trimmed(&from_digit_values(&values, radix)), &hunks[..]);
+            }
+        }
+    }
+    #[test]
+    fn leading_and_inner_zeros() {
+        let mut values = vec![0u8; 2000];
+        values[500] = 7;
+        values[1999] = 1;
+        let hunks = from_digit_values(&values, 10);
+        let digits = to_digits(&hunks, 10);
+        assert_eq!(digits.len(), 1500);
+        assert!(digits.starts_with('7'));
+        assert!(digits.ends_with("00001"));
+        assert_eq!(to_digits(&[], 10), "0");
+    }
+    #[test]
+    fn power_of_two_radices() {
+        let hunks = random_hunks(&mut 0x2545F4914F6CDD1Du64, 50);
+        for radix in [2, 4, 8, 16, 32] {
+            let values: Vec<u8> = to_digits(&hunks, radix)
+                .bytes()
+                .map(|digit| char::from(digit).to_digit(36).unwrap() as u8)
+                .collect();
+            assert_eq!(trimmed(&from_digit_values(&values, radix)), &hunks[..]);
+        }
+    }
+}
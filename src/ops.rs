@@ -1,163 +1,354 @@
+use crate::naturals::Hunk;
 use crate::naturals::Natural;
-use crate::naturals::Natural::{Big, Small};
-use crate::naturals::iter::BitIter;
-use std::cmp::Ordering;
-use std::iter;
-use std::iter::repeat_n;
-use std::ops::{Add, Div, Mul, Rem, Shl, Sub};
-
-impl Add<Natural> for Natural {
-    type Output = Natural;
-    fn add(self, rhs: Natural) -> Natural {
-        let mut lhs = match self {
-            Small(hunk) => vec![hunk],
-            Big(hunks) => hunks,
-        };
-        let mut rhs = match rhs {
-            Small(hunk) => vec![hunk],
-            Big(hunks) => hunks,
-        };
-        match lhs.len().cmp(&rhs.len()) {
-            Ordering::Less => lhs.extend(repeat_n(0, rhs.len() - lhs.len())),
-            Ordering::Greater => rhs.extend(repeat_n(0, lhs.len() - rhs.len())),
-            Ordering::Equal => {}
+use crate::naturals::hunks::INLINE_HUNKS;
+use crate::naturals::{arith, div, mul};
+use std::ops::{
+    Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Div, DivAssign,
+    Mul, MulAssign, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign,
+};
+
+/// Derives the owned and mixed operator impls from `$assign<&Natural> for Natural`.
+///
+/// Owned left hand sides are updated in place and returned, so their hunk buffer is reused.
+macro_rules! forward_binop {
+    ($($imp:ident $method:ident $assign:ident $assign_method:ident)*) => ($(
+        impl $imp<Natural> for Natural {
+            type Output = Natural;
+            fn $method(mut self, rhs: Natural) -> Natural {
+                self.$assign_method(&rhs);
+                self
+            }
         }
-        let mut carry = 0usize;
-        let mut new: Vec<usize> = lhs
-            .iter()
-            .zip(rhs)
-            .map(|(lhs, rhs)| {
-                let (sum, overflow) = lhs.overflowing_add(rhs);
-                let sum = sum + carry;
-                carry = if overflow { 1 } else { 0 };
-                sum
-            })
-            .collect();
-        if carry > 0 {
-            new.push(carry);
+        impl $imp<&Natural> for Natural {
+            type Output = Natural;
+            fn $method(mut self, rhs: &Natural) -> Natural {
+                self.$assign_method(rhs);
+                self
+            }
+        }
+        impl $imp<Natural> for &Natural {
+            type Output = Natural;
+            fn $method(self, rhs: Natural) -> Natural {
+                self.$method(&rhs)
+            }
         }
-        Big(new).trim()
+        impl $assign<Natural> for Natural {
+            fn $assign_method(&mut self, rhs: Natural) {
+                self.$assign_method(&rhs);
+            }
+        }
+    )*)
+}
+
+forward_binop! {
+    Add add AddAssign add_assign
+    Mul mul MulAssign mul_assign
+    Div div DivAssign div_assign
+    Rem rem RemAssign rem_assign
+}
+
+impl AddAssign<&Natural> for Natural {
+    fn add_assign(&mut self, rhs: &Natural) {
+        if let (Some(lhs), Some(rhs)) = (self.as_hunk(), rhs.as_hunk())
+            && let Some(sum) = lhs.checked_add(rhs)
+        {
+            *self = Natural::from_hunk(sum);
+            return;
+        }
+        let rhs = rhs.hunks();
+        let hunks = self.hunks_mut();
+        if hunks.len() < rhs.len() {
+            hunks.resize(rhs.len(), 0);
+        }
+        if arith::add_assign(hunks, rhs) {
+            hunks.push(1);
+        }
+        self.trim_in_place();
+    }
+}
+
+impl Add<&Natural> for &Natural {
+    type Output = Natural;
+    fn add(self, rhs: &Natural) -> Natural {
+        let (long, short) = if self.len() >= rhs.len() {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+        long.clone() + short
     }
 }
+
 #[cfg(test)]
 mod add_test {
-    use crate::naturals::Natural::{Big, Small};
+    use crate::naturals::Hunk;
+    use crate::naturals::Natural;
     #[test]
     fn big_lhs_rhs_0() {
-        let lhs = Big(vec![1, 1, 1]);
-        let rhs = Big(vec![1, 1]);
-        let expected = Big(vec![2, 2, 1]);
+        let lhs = Natural::from_hunks(vec![1, 1, 1]);
+        let rhs = Natural::from_hunks(vec![1, 1]);
+        let expected = Natural::from_hunks(vec![2, 2, 1]);
         assert_eq!(expected, lhs + rhs);
     }
     #[test]
     fn big_lhs_rhs_1() {
-        let lhs = Big(vec![1, 1]);
-        let rhs = Big(vec![1, 1, 2]);
-        let expected = Big(vec![2, 2, 2]);
+        let lhs = Natural::from_hunks(vec![1, 1]);
+        let rhs = Natural::from_hunks(vec![1, 1, 2]);
+        let expected = Natural::from_hunks(vec![2, 2, 2]);
         assert_eq!(expected, lhs + rhs);
     }
     #[test]
     fn big_lhs_rhs_2() {
-        let lhs = Big(vec![1, usize::MAX, 1, 1]);
-        let rhs = Big(vec![1, 1]);
-        let expected = Big(vec![2, 0, 2, 1]);
+        let lhs = Natural::from_hunks(vec![1, Hunk::MAX, 1, 1]);
+        let rhs = Natural::from_hunks(vec![1, 1]);
+        let expected = Natural::from_hunks(vec![2, 0, 2, 1]);
         assert_eq!(expected, lhs + rhs);
     }
     #[test]
     fn big_lhs_rhs_3() {
-        let lhs = Small(0);
-        let rhs = Small(0);
-        let expected = Small(0);
+        let lhs = Natural::from_hunk(0);
+        let rhs = Natural::from_hunk(0);
+        let expected = Natural::from_hunk(0);
         assert_eq!(expected, lhs + rhs);
     }
 }
 
+impl SubAssign<&Natural> for Natural {
+    /// # Panics
+    /// If `rhs` is larger than `self`, like the primitive unsigned integers in debug builds.
+    fn sub_assign(&mut self, rhs: &Natural) {
+        if let (Some(lhs), Some(rhs)) = (self.as_hunk(), rhs.as_hunk()) {
+            let difference = lhs
+                .checked_sub(rhs)
+                .expect("attempt to subtract with overflow");
+            *self = Natural::from_hunk(difference);
+            return;
+        }
+        let borrow = arith::sub_assign(self.hunks_mut(), rhs.hunks());
+        assert!(!borrow, "attempt to subtract with overflow");
+        self.trim_in_place();
+    }
+}
+
+impl SubAssign<Natural> for Natural {
+    fn sub_assign(&mut self, rhs: Natural) {
+        *self -= &rhs;
+    }
+}
+
+impl Sub<&Natural> for Natural {
+    type Output = Option<Natural>;
+    fn sub(mut self, rhs: &Natural) -> Self::Output {
+        if self < *rhs {
+            return None;
+        }
+        self -= rhs;
+        Some(self)
+    }
+}
+
 impl Sub<Natural> for Natural {
     type Output = Option<Natural>;
     fn sub(self, rhs: Natural) -> Self::Output {
-        match (self, rhs) {
-            (Small(lhs), Small(rhs)) => Some(Small(lhs.checked_sub(rhs)?)),
-            (Small(_), Big(_)) => None,
-            (Big(lhs), Big(rhs)) => {
-                if lhs.len() < rhs.len() {
-                    None
-                } else {
-                    let mut carry = 0usize;
-                    let new: Vec<usize> = lhs
-                        .iter()
-                        .zip(rhs.iter().chain(iter::repeat(&0usize)))
-                        .map(|(lhs, rhs)| {
-                            let (diff, overflow0) = lhs.overflowing_sub(*rhs);
-                            let (diff, overflow1) = diff.overflowing_sub(carry);
-                            carry = if overflow0 | overflow1 { 1 } else { 0 };
-                            diff
-                        })
-                        .collect();
-                    if carry == 0 { Some(Big(new)) } else { None }
-                }
-            }
-            (lhs, Small(rhs)) => lhs.sub(Big(vec![rhs])),
+        self - &rhs
+    }
+}
+
+impl Sub<&Natural> for &Natural {
+    type Output = Option<Natural>;
+    fn sub(self, rhs: &Natural) -> Self::Output {
+        if self < rhs {
+            return None;
         }
+        let mut difference = self.clone();
+        difference -= rhs;
+        Some(difference)
     }
 }
 
-#[cfg(target_pointer_width = "64")]
-impl Mul<Natural> for Natural {
-    type Output = Natural;
-    fn mul(self, rhs: Natural) -> Self::Output {
-        let lhs = match self {
-            Small(lhs) => vec![lhs],
-            Big(lhs) => lhs,
-        };
-        let rhs = match rhs {
-            Small(rhs) => vec![rhs],
-            Big(rhs) => rhs,
-        };
-        let mut prod_sum: Vec<Natural> = Vec::new();
-        for (idx_l, item_l) in lhs.iter().enumerate() {
-            for (idx_r, item_r) in rhs.iter().enumerate() {
-                let n = Natural::new((*item_l as u128).mul(*item_r as u128));
-                #[allow(clippy::suspicious_arithmetic_impl)]
-                prod_sum.push(n.shift_up(idx_l + idx_r));
-            }
+impl Sub<Natural> for &Natural {
+    type Output = Option<Natural>;
+    fn sub(self, rhs: Natural) -> Self::Output {
+        self - &rhs
+    }
+}
+#[cfg(test)]
+mod sub_test {
+    use crate::naturals::Hunk;
+    use crate::naturals::Natural;
+    #[test]
+    fn big_lhs_rhs_equal() {
+        let lhs = Natural::from_hunks(vec![1, 2, 3]);
+        let rhs = Natural::from_hunks(vec![1, 2, 3]);
+        let diff = (lhs - rhs).unwrap();
+        assert!(diff.is_small());
+        assert_eq!(Natural::from_hunk(0), diff);
+    }
+    #[test]
+    fn big_lhs_rhs_borrow() {
+        let lhs = Natural::from_hunks(vec![0, 0, 1]);
+        let rhs = Natural::from_hunk(1);
+        assert_eq!(
+            Some(Natural::from_hunks(vec![Hunk::MAX, Hunk::MAX])),
+            lhs - rhs
+        );
+    }
+    #[test]
+    fn big_lhs_rhs_negative() {
+        assert_eq!(
+            None,
+            Natural::from_hunks(vec![1, 2]) - Natural::from_hunks(vec![2, 2])
+        );
+        assert_eq!(
+            None,
+            Natural::from_hunk(5) - Natural::from_hunks(vec![0, 1])
+        );
+    }
+    #[test]
+    fn big_lhs_rhs_trimmed() {
+        let diff = (Natural::from_hunks(vec![5, 7]) - Natural::from_hunks(vec![3, 7])).unwrap();
+        assert!(diff.is_small());
+        assert_eq!(Natural::from_hunk(2), diff);
+    }
+}
+
+#[cfg(test)]
+mod assign_tests {
+    use crate::naturals::Hunk;
+    use crate::naturals::Natural;
+    #[test]
+    fn borrowed_operands() {
+        let lhs = Natural::from_hunks(vec![Hunk::MAX, Hunk::MAX]);
+        let rhs = Natural::from_hunk(1);
+        assert_eq!(&lhs + &rhs, Natural::from_hunks(vec![0, 0, 1]));
+        assert_eq!(
+            &lhs - &rhs,
+            Some(Natural::from_hunks(vec![Hunk::MAX - 1, Hunk::MAX]))
+        );
+        assert_eq!(&rhs - &lhs, None);
+        assert_eq!(
+            &lhs * &Natural::from_hunk(2),
+            Natural::from_hunks(vec![Hunk::MAX - 1, Hunk::MAX, 1])
+        );
+        assert_eq!(
+            &lhs / &Natural::from_hunks(vec![0, 1]),
+            Natural::from_hunk(Hunk::MAX)
+        );
+        assert_eq!(
+            &lhs % &Natural::from_hunks(vec![0, 1]),
+            Natural::from_hunk(Hunk::MAX)
+        );
+        assert_eq!(
+            &rhs << &Natural::from_hunk(Hunk::from(Hunk::BITS)),
+            Natural::from_hunks(vec![0, 1])
+        );
+        assert_eq!(lhs.clone() + &rhs, &lhs + rhs.clone());
+    }
+    #[test]
+    fn assign_in_place() {
+        let mut acc = Natural::ZERO;
+        for _ in 0..4 {
+            acc += &Natural::from_hunk(Hunk::MAX);
         }
-        #[allow(clippy::suspicious_arithmetic_impl)]
-        prod_sum
-            .iter_mut()
-            .reduce(|acc, next| {
-                *acc = acc.clone() + next.to_owned();
-                acc
-            })
-            .unwrap()
-            .clone()
+        assert_eq!(acc, Natural::from_hunks(vec![Hunk::MAX - 3, 3]));
+        acc -= Natural::from_hunk(Hunk::MAX - 3);
+        assert_eq!(acc, Natural::from_hunks(vec![0, 3]));
+        acc *= &Natural::from_hunks(vec![0, 1]);
+        assert_eq!(acc, Natural::from_hunks(vec![0, 0, 3]));
+        acc /= Natural::from_hunks(vec![0, 0, 1]);
+        assert_eq!(acc, Natural::from_hunk(3));
+        acc <<= Natural::from_hunk(Hunk::from(Hunk::BITS) + 1);
+        assert_eq!(acc, Natural::from_hunks(vec![0, 6]));
+        acc %= &Natural::from_hunk(4);
+        assert_eq!(acc, Natural::from_hunk(0));
+    }
+    #[test]
+    fn sub_assign_trims() {
+        let mut acc = Natural::from_hunks(vec![5, 1]);
+        acc -= &Natural::from_hunks(vec![4, 1]);
+        assert!(acc.is_small());
+        assert_eq!(acc, Natural::from_hunk(1));
+    }
+    #[test]
+    #[should_panic(expected = "attempt to subtract with overflow")]
+    fn sub_assign_underflow() {
+        let mut acc = Natural::from_hunk(1);
+        acc -= Natural::from_hunks(vec![0, 1]);
     }
 }
 
-impl Natural {
-    fn shift_up(self, n: usize) -> Natural {
-        if n == 0 {
-            return self;
-        } else if self.len().checked_add(n).is_none() {
-            panic!("Shifting up would create an inner vec too big for indexing");
+impl MulAssign<&Natural> for Natural {
+    fn mul_assign(&mut self, rhs: &Natural) {
+        *self = &*self * rhs;
+    }
+}
+
+impl Mul<&Natural> for &Natural {
+    type Output = Natural;
+    fn mul(self, rhs: &Natural) -> Natural {
+        if let (Some(lhs), Some(rhs)) = (self.as_hunk(), rhs.as_hunk()) {
+            return Natural::new(lhs as u128 * rhs as u128);
         }
-        let mut shifted = [0usize].repeat(n);
-        match self {
-            Small(single) => shifted.push(single),
-            Big(vec) => shifted.extend(vec),
+        let (lhs, rhs) = (self.hunks(), rhs.hunks());
+        if lhs.len() <= INLINE_HUNKS && rhs.len() <= INLINE_HUNKS {
+            let mut product = [0; 2 * INLINE_HUNKS];
+            mul::mul_into(&mut product, lhs, rhs);
+            return Natural::from_slice(&product);
         }
-        Big(shifted)
+        Natural::from_hunks(mul::mul(lhs, rhs))
     }
-    /// Exponentiation with Horner's method.
-    /// See [Wikipedia](https://en.wikipedia.org/wiki/Horner%27s_method)
-    /// or [here](https://www.brainkart.com/article/Horner---s-Rule-and-Binary-Exponentiation_8034/)
-    /// for explanation.
-    ///
-    /// In particular the paragraph **Binary Exponentiation** with this image.
-    /// ![Image](https://img.brainkart.com/imagebk9/F5M5pU5.jpg)
+}
+#[cfg(test)]
+mod mul_tests {
+    use crate::naturals::Hunk;
+    use crate::naturals::Natural;
+    #[test]
+    fn mul_small() {
+        assert_eq!(
+            Natural::from_hunk(6) * Natural::from_hunk(7),
+            Natural::from_hunk(42)
+        );
+        assert_eq!(
+            Natural::from_hunk(Hunk::MAX) * Natural::from_hunk(0),
+            Natural::from_hunk(0)
+        );
+        assert_eq!(
+            Natural::from_hunk(Hunk::MAX) * Natural::from_hunk(Hunk::MAX),
+            Natural::from_hunks(vec![1, Hunk::MAX - 1])
+        );
+    }
+    #[test]
+    fn mul_big() {
+        let lhs = Natural::new(u128::MAX);
+        assert_eq!(
+            lhs.clone() * lhs,
+            Natural::from_hunks(vec![1, 0, Hunk::MAX - 1, Hunk::MAX])
+        );
+        assert_eq!(
+            Natural::from_hunks(vec![0, 1]) * Natural::from_hunks(vec![0, 0, 1]),
+            Natural::from_hunks(vec![0, 0, 0, 1])
+        );
+    }
+    #[test]
+    fn mul_above_thresholds() {
+        // (2^n - 1)^2 = 2^2n - 2^(n+1) + 1
+        for len in [40, 200, 1000] {
+            let ones = Natural::from_hunks(vec![Hunk::MAX; len]);
+            let mut expected = vec![0; 2 * len];
+            expected[0] = 1;
+            expected[len] = Hunk::MAX - 1;
+            expected[len + 1..].fill(Hunk::MAX);
+            assert_eq!(ones.clone() * ones, Natural::from_hunks(expected));
+        }
+    }
+}
+
+impl Natural {
+    /// `self` to the power `exp`, by square-and-multiply over the bits of `exp` from the top.
     pub fn pow(self, exp: Natural) -> Natural {
-        BitIter::from(exp).fold(Natural::ONE, |acc, bit| {
-            let acc = acc.clone() * acc;
-            if bit { acc * self.clone() } else { acc }
+        exp.bits().fold(Natural::ONE, |acc, bit| {
+            let acc = &acc * &acc;
+            if bit { acc * &self } else { acc }
         })
     }
 }
@@ -165,125 +356,785 @@ impl Natural {
 #[cfg(test)]
 mod pow_tests {
     use crate::naturals::Natural;
-    use crate::naturals::Natural::Small;
     #[test]
     fn pow_0() {
         assert_eq!(
-            Natural::TWO.pow(Small(100)),
+            Natural::TWO.pow(Natural::from_hunk(100)),
             "1267650600228229401496703205376".parse().unwrap()
         );
     }
     #[test]
     fn pow_1() {
-        assert_eq!(Small(5).pow(Small(5)), Small(3125));
+        assert_eq!(
+            Natural::from_hunk(5).pow(Natural::from_hunk(5)),
+            Natural::from_hunk(3125)
+        );
+    }
+    #[test]
+    fn pow_zero_exponent() {
+        assert_eq!(Natural::from_hunk(5).pow(Natural::ZERO), Natural::ONE);
+        assert_eq!(Natural::ZERO.pow(Natural::ZERO), Natural::ONE);
     }
 }
 
-impl Div<Natural> for Natural {
-    type Output = Natural;
-    // TODO implement proper long division
-    fn div(self, rhs: Self) -> Self::Output {
-        assert_ne!(rhs, Natural::ZERO, "division by zero");
-        let mut remainder = self;
-        let mut quotient = Natural::ZERO;
-        while remainder >= rhs {
-            remainder = (remainder - rhs.clone()).expect("It was tested remainder > rhs");
-            quotient = quotient + Natural::ONE;
+impl Natural {
+    /// The integer square root, the largest `root` with `root · root <= self`.
+    ///
+    /// Newton's iteration from a power of two above the root; it decreases monotonically
+    /// until it reaches the floor of the root.
+    pub fn isqrt(&self) -> Natural {
+        if let Some(hunk) = self.as_hunk() {
+            return Natural::from_hunk(hunk.isqrt());
+        }
+        let mut root = Natural::ZERO;
+        root.set_bit(self.bit_len().div_ceil(2));
+        loop {
+            let next = (self / &root + &root) >> 1u8;
+            if next >= root {
+                return root;
+            }
+            root = next;
+        }
+    }
+}
+#[cfg(test)]
+mod isqrt_tests {
+    use crate::naturals::Natural;
+    #[test]
+    fn small() {
+        for n in [0u64, 1, 2, 3, 4, 15, 16, 17, 99, 100, u64::MAX] {
+            assert_eq!(Natural::new(n).isqrt(), Natural::new(n.isqrt()), "{n}");
+        }
+    }
+    #[test]
+    fn big() {
+        let root = Natural::new(3u8).pow(Natural::new(100u8));
+        let square = &root * &root;
+        assert_eq!(square.isqrt(), root);
+        assert_eq!(
+            (&square - &Natural::ONE).unwrap().isqrt(),
+            (&root - &Natural::ONE).unwrap()
+        );
+        assert_eq!((square + &root).isqrt(), root);
+        assert_eq!(Natural::new(u128::MAX).isqrt(), Natural::new(u64::MAX));
+    }
+}
+
+impl Natural {
+    /// Quotient and remainder of `self / rhs`, computed together by one long division.
+    ///
+    /// # Panics
+    /// If `rhs` is zero.
+    pub fn div_rem(&self, rhs: &Natural) -> (Natural, Natural) {
+        assert_ne!(*rhs, Natural::ZERO, "division by zero");
+        if let (Some(lhs), Some(rhs)) = (self.as_hunk(), rhs.as_hunk()) {
+            return (Natural::from_hunk(lhs / rhs), Natural::from_hunk(lhs % rhs));
         }
-        quotient
+        let (quotient, remainder) = div::div_rem(self.hunks(), rhs.hunks());
+        (
+            Natural::from_hunks(quotient),
+            Natural::from_hunks(remainder),
+        )
     }
 }
 
-impl Rem<Natural> for Natural {
+impl DivAssign<&Natural> for Natural {
+    fn div_assign(&mut self, rhs: &Natural) {
+        *self = self.div_rem(rhs).0;
+    }
+}
+
+impl Div<&Natural> for &Natural {
     type Output = Natural;
-    fn rem(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (Small(lhs), Small(rhs)) => Small(lhs.rem(rhs)),
-            (Big(_lhs), Small(_rhs)) => todo!(),
-            (Small(lhs), Big(_)) => Small(lhs),
-            (Big(_lhs), Big(_rhs)) => todo!(),
-        }
+    fn div(self, rhs: &Natural) -> Natural {
+        self.div_rem(rhs).0
     }
 }
 
-impl Shl<Natural> for Natural {
+impl RemAssign<&Natural> for Natural {
+    fn rem_assign(&mut self, rhs: &Natural) {
+        *self = self.div_rem(rhs).1;
+    }
+}
+
+impl Rem<&Natural> for &Natural {
     type Output = Natural;
-    fn shl(mut self, rhs: Natural) -> Self::Output {
-        let secure_shift = Natural::new((isize::MAX >> size_of::<usize>().ilog2()).unsigned_abs())
-            * Natural::new(usize::BITS);
-        assert!(
-            rhs < secure_shift,
-            "Shifting {secure_shift:?} or more times is impossible as it creates more then isize::MAX bytes"
-        );
-        let hunk_size = Small(usize::BITS as usize);
-        if rhs >= hunk_size {
-            let new_hunks = rhs.clone() / hunk_size.clone();
-            assert!(new_hunks.is_small());
-            assert!(
-                new_hunks.first_hunk() + self.len() < Natural::max_hunks(),
-                "Shifting {:?} times would create more then isize::MAX bytes",
-                rhs.clone()
-            );
-            self = self.shift_up(new_hunks.first_hunk());
-        }
-        let rhs: usize = (rhs % hunk_size).first_hunk();
-        if rhs == 0 {
-            self
-        } else {
-            match self {
-                Small(hunk) => Big(vec![hunk << rhs, hunk >> (usize::BITS as usize - rhs)]).trim(),
-                Big(hunks) => {
-                    let mut upper: Vec<usize> = hunks.iter().map(|x| x << rhs).collect();
-                    upper.push(0);
-                    let mut lower: Vec<usize> = vec![0];
-                    lower.extend(hunks.iter().map(|x| x >> (usize::BITS - (rhs as u32))));
-                    Big(upper
-                        .iter()
-                        .zip(lower)
-                        .map(|(upper, lower)| upper + lower)
-                        .collect())
-                    .trim()
+    fn rem(self, rhs: &Natural) -> Natural {
+        self.div_rem(rhs).1
+    }
+}
+#[cfg(test)]
+mod div_tests {
+    use crate::naturals::{Hunk, Natural, random_hunks};
+
+    #[test]
+    fn div_rem_small() {
+        assert_eq!(
+            Natural::from_hunk(17).div_rem(&Natural::from_hunk(5)),
+            (Natural::from_hunk(3), Natural::from_hunk(2))
+        );
+        assert_eq!(
+            Natural::from_hunk(17) / Natural::from_hunk(5),
+            Natural::from_hunk(3)
+        );
+        assert_eq!(
+            Natural::from_hunk(17) % Natural::from_hunk(5),
+            Natural::from_hunk(2)
+        );
+        assert_eq!(
+            Natural::from_hunk(3).div_rem(&Natural::from_hunks(vec![0, 1])),
+            (Natural::from_hunk(0), Natural::from_hunk(3))
+        );
+    }
+    #[test]
+    fn div_rem_by_hunk() {
+        let lhs: Natural = format!("1{}", "0".repeat(199)).parse().unwrap();
+        let (quotient, remainder) = lhs.div_rem(&Natural::from_hunk(3));
+        assert_eq!(remainder, Natural::from_hunk(1));
+        assert_eq!(quotient * Natural::from_hunk(3) + remainder, lhs);
+    }
+    #[test]
+    fn div_rem_roundtrip() {
+        let mut seed = 0x2545F4914F6CDD1Du64;
+        for lhs_len in 1..9 {
+            for rhs_len in 1..=lhs_len {
+                let lhs = Natural::from_hunks(random_hunks(&mut seed, lhs_len));
+                let rhs = Natural::from_hunks(random_hunks(&mut seed, rhs_len));
+                if rhs == Natural::ZERO {
+                    continue;
                 }
+                let (quotient, remainder) = lhs.div_rem(&rhs);
+                assert!(remainder < rhs);
+                assert_eq!(quotient * rhs + remainder, lhs);
             }
         }
     }
+    #[test]
+    fn div_rem_add_back() {
+        // Shape that makes the estimated quotient hunk one too large.
+        let lhs = Natural::from_hunks(vec![
+            0,
+            0,
+            1 << (Hunk::BITS - 1),
+            (1 << (Hunk::BITS - 1)) - 1,
+        ]);
+        let rhs = Natural::from_hunks(vec![1, 0, 1 << (Hunk::BITS - 1)]);
+        let (quotient, remainder) = lhs.div_rem(&rhs);
+        assert!(remainder < rhs);
+        assert_eq!(quotient * rhs + remainder, lhs);
+    }
+    #[test]
+    #[should_panic(expected = "division by zero")]
+    fn div_by_zero() {
+        let _ = Natural::from_hunks(vec![1, 1]) / Natural::ZERO;
+    }
+}
+
+impl Natural {
+    /// The greatest common divisor, with `gcd(0, 0) == 0`.
+    ///
+    /// Euclid's algorithm reduces the values with long divisions until both fit into a hunk,
+    /// the binary algorithm finishes from there.
+    pub fn gcd(&self, other: &Natural) -> Natural {
+        let (mut lhs, mut rhs) = (self.clone(), other.clone());
+        loop {
+            if let (Some(lhs), Some(rhs)) = (lhs.as_hunk(), rhs.as_hunk()) {
+                return Natural::from_hunk(gcd_hunk(lhs, rhs));
+            }
+            if rhs == Natural::ZERO {
+                return lhs;
+            }
+            let remainder = &lhs % &rhs;
+            lhs = rhs;
+            rhs = remainder;
+        }
+    }
+}
+
+/// Stein's binary gcd: strip the common power of two, then subtract the smaller odd value.
+fn gcd_hunk(mut lhs: Hunk, mut rhs: Hunk) -> Hunk {
+    if lhs == 0 || rhs == 0 {
+        return lhs | rhs;
+    }
+    let shift = (lhs | rhs).trailing_zeros();
+    lhs >>= lhs.trailing_zeros();
+    loop {
+        rhs >>= rhs.trailing_zeros();
+        if lhs > rhs {
+            std::mem::swap(&mut lhs, &mut rhs);
+        }
+        rhs -= lhs;
+        if rhs == 0 {
+            return lhs << shift;
+        }
+    }
+}
+#[cfg(test)]
+mod gcd_tests {
+    use crate::naturals::Natural;
+    #[test]
+    fn small() {
+        let gcd = |lhs: u64, rhs: u64| Natural::new(lhs).gcd(&Natural::new(rhs));
+        assert_eq!(gcd(0, 0), Natural::ZERO);
+        assert_eq!(gcd(0, 12), Natural::new(12u8));
+        assert_eq!(gcd(12, 0), Natural::new(12u8));
+        assert_eq!(gcd(48, 18), Natural::new(6u8));
+        assert_eq!(gcd(17, 5), Natural::ONE);
+        assert_eq!(gcd(u64::MAX, u64::MAX - 1), Natural::ONE);
+        assert_eq!(gcd(1 << 40, 3 << 20), Natural::new(1u32 << 20));
+    }
+    #[test]
+    fn big() {
+        let common = Natural::new(3u8).pow(Natural::new(90u8));
+        let lhs = &common * Natural::new(2u8).pow(Natural::new(70u8));
+        let rhs = &common * Natural::new(5u8).pow(Natural::new(60u8));
+        assert_eq!(lhs.gcd(&rhs), common);
+        assert_eq!(rhs.gcd(&lhs), common);
+        assert_eq!(lhs.gcd(&Natural::ZERO), lhs);
+        assert_eq!(lhs.gcd(&Natural::new(7u8)), Natural::ONE);
+    }
+}
+
+impl Natural {
+    /// The value of `self` as a shift count, if it fits into a `usize`.
+    fn shift_count(&self) -> Option<usize> {
+        match self.hunks() {
+            [] => Some(0),
+            [hunk] => usize::try_from(*hunk).ok(),
+            _ => None,
+        }
+    }
+    /// `self <<= bits`, moving whole hunks first and then the remaining bits in place.
+    fn shl_assign_bits(&mut self, bits: usize) {
+        let (new_hunks, bits) = (bits / Hunk::BITS as usize, bits as u32 % Hunk::BITS);
+        if let Some(hunk) = self.as_hunk() {
+            if hunk == 0 {
+                return;
+            }
+            if new_hunks == 0 && hunk.leading_zeros() >= bits {
+                *self = Natural::from_hunk(hunk << bits);
+                return;
+            }
+        }
+        assert!(
+            new_hunks < Natural::max_hunks() - self.len(),
+            "Shifting {new_hunks} hunks up would create more then isize::MAX bytes"
+        );
+        let hunks = self.hunks_mut();
+        if bits != 0 {
+            let top = hunks[hunks.len() - 1] >> (Hunk::BITS - bits);
+            for idx in (1..hunks.len()).rev() {
+                hunks[idx] = (hunks[idx] << bits) | (hunks[idx - 1] >> (Hunk::BITS - bits));
+            }
+            hunks[0] <<= bits;
+            if top != 0 {
+                hunks.push(top);
+            }
+        }
+        hunks.insert_zeros_front(new_hunks);
+        self.trim_in_place();
+    }
+    /// `self >>= bits`, dropping whole hunks first and then the remaining bits in place.
+    fn shr_assign_bits(&mut self, bits: usize) {
+        if let Some(hunk) = self.as_hunk() {
+            let shifted = hunk
+                .checked_shr(bits.try_into().unwrap_or(u32::MAX))
+                .unwrap_or(0);
+            *self = Natural::from_hunk(shifted);
+            return;
+        }
+        let (dropped, bits) = (bits / Hunk::BITS as usize, bits as u32 % Hunk::BITS);
+        let hunks = self.hunks_mut();
+        if dropped >= hunks.len() {
+            *self = Natural::ZERO;
+            return;
+        }
+        hunks.drain_front(dropped);
+        if bits != 0 {
+            for idx in 0..hunks.len() - 1 {
+                hunks[idx] = (hunks[idx] >> bits) | (hunks[idx + 1] << (Hunk::BITS - bits));
+            }
+            let top = hunks.len() - 1;
+            hunks[top] >>= bits;
+        }
+        self.trim_in_place();
+    }
+    /// `self >> bits` without copying the hunks that are shifted out.
+    fn shr_bits(&self, bits: usize) -> Natural {
+        let dropped = bits / Hunk::BITS as usize;
+        let hunks = self.hunks();
+        if dropped >= hunks.len() {
+            return Natural::ZERO;
+        }
+        let mut shifted = Natural::from_slice(&hunks[dropped..]);
+        shifted.shr_assign_bits(bits % Hunk::BITS as usize);
+        shifted
+    }
+}
+
+impl ShlAssign<&Natural> for Natural {
+    fn shl_assign(&mut self, rhs: &Natural) {
+        match rhs.shift_count() {
+            Some(bits) => self.shl_assign_bits(bits),
+            None if *self == Natural::ZERO => {}
+            None => panic!(
+                "Shifting {rhs:?} times is impossible as it creates more then isize::MAX bytes"
+            ),
+        }
+    }
+}
+
+impl ShrAssign<&Natural> for Natural {
+    fn shr_assign(&mut self, rhs: &Natural) {
+        match rhs.shift_count() {
+            Some(bits) => self.shr_assign_bits(bits),
+            None => *self = Natural::ZERO,
+        }
+    }
+}
+
+impl Shr<&Natural> for &Natural {
+    type Output = Natural;
+    fn shr(self, rhs: &Natural) -> Natural {
+        match rhs.shift_count() {
+            Some(bits) => self.shr_bits(bits),
+            None => Natural::ZERO,
+        }
+    }
+}
+
+impl Shl<&Natural> for &Natural {
+    type Output = Natural;
+    fn shl(self, rhs: &Natural) -> Natural {
+        self.clone() << rhs
+    }
+}
+
+forward_binop! {
+    Shl shl ShlAssign shl_assign
+    Shr shr ShrAssign shr_assign
+}
+
+/// Shifts by primitive counts. Signed counts must not be negative, just like for the primitives.
+macro_rules! impl_shift_primitive {
+    (unsigned: $($u:ty)*; signed: $($i:ty)*) => {
+        $(
+            impl ShlAssign<$u> for Natural {
+                fn shl_assign(&mut self, rhs: $u) {
+                    match usize::try_from(rhs) {
+                        Ok(bits) => self.shl_assign_bits(bits),
+                        Err(_) if *self == Natural::ZERO => {}
+                        Err(_) => panic!(
+                            "Shifting {rhs} times is impossible as it creates more then isize::MAX bytes"
+                        ),
+                    }
+                }
+            }
+            impl ShrAssign<$u> for Natural {
+                fn shr_assign(&mut self, rhs: $u) {
+                    self.shr_assign_bits(usize::try_from(rhs).unwrap_or(usize::MAX));
+                }
+            }
+            impl Shl<$u> for Natural {
+                type Output = Natural;
+                fn shl(mut self, rhs: $u) -> Natural {
+                    self <<= rhs;
+                    self
+                }
+            }
+            impl Shl<$u> for &Natural {
+                type Output = Natural;
+                fn shl(self, rhs: $u) -> Natural {
+                    self.clone() << rhs
+                }
+            }
+            impl Shr<$u> for Natural {
+                type Output = Natural;
+                fn shr(mut self, rhs: $u) -> Natural {
+                    self >>= rhs;
+                    self
+                }
+            }
+            impl Shr<$u> for &Natural {
+                type Output = Natural;
+                fn shr(self, rhs: $u) -> Natural {
+                    self.shr_bits(usize::try_from(rhs).unwrap_or(usize::MAX))
+                }
+            }
+        )*
+        $(
+            impl ShlAssign<$i> for Natural {
+                fn shl_assign(&mut self, rhs: $i) {
+                    assert!(rhs >= 0, "attempt to shift left by a negative count");
+                    *self <<= rhs.unsigned_abs();
+                }
+            }
+            impl ShrAssign<$i> for Natural {
+                fn shr_assign(&mut self, rhs: $i) {
+                    assert!(rhs >= 0, "attempt to shift right by a negative count");
+                    *self >>= rhs.unsigned_abs();
+                }
+            }
+            impl Shl<$i> for Natural {
+                type Output = Natural;
+                fn shl(mut self, rhs: $i) -> Natural {
+                    self <<= rhs;
+                    self
+                }
+            }
+            impl Shl<$i> for &Natural {
+                type Output = Natural;
+                fn shl(self, rhs: $i) -> Natural {
+                    self.clone() << rhs
+                }
+            }
+            impl Shr<$i> for Natural {
+                type Output = Natural;
+                fn shr(mut self, rhs: $i) -> Natural {
+                    self >>= rhs;
+                    self
+                }
+            }
+            impl Shr<$i> for &Natural {
+                type Output = Natural;
+                fn shr(self, rhs: $i) -> Natural {
+                    assert!(rhs >= 0, "attempt to shift right by a negative count");
+                    self >> rhs.unsigned_abs()
+                }
+            }
+        )*
+    };
+}
+
+impl_shift_primitive! {
+    unsigned: u8 u16 u32 u64 u128 usize;
+    signed: i8 i16 i32 i64 i128 isize
 }
 #[cfg(test)]
-#[cfg(target_pointer_width = "64")]
 mod shl_tests {
     use crate::naturals::Natural;
-    use crate::naturals::Natural::Small;
 
     #[test]
     fn shl_inside_small() {
-        let expected = Natural::Small(0);
-        let output = expected.clone() << Small(1);
+        let expected = Natural::from_hunk(0);
+        let output = expected.clone() << Natural::from_hunk(1);
         assert_eq!(expected, output);
 
-        let expected = Natural::Small(8);
-        let output = Natural::Small(1) << Small(3);
+        let expected = Natural::from_hunk(8);
+        let output = Natural::from_hunk(1) << Natural::from_hunk(3);
         assert_eq!(expected, output);
 
-        let expected = Natural::Small(0b1010001010000000);
-        let output = Natural::Small(0b101000101) << Small(7);
+        let expected = Natural::from_hunk(0b1010001010000000);
+        let output = Natural::from_hunk(0b101000101) << Natural::from_hunk(7);
         assert_eq!(expected, output);
     }
     #[test]
     fn shl_small_into_big() {
-        let expected = Natural::Big(vec![0, 1]);
-        let output = Natural::Small(1) << Small(64);
+        let expected = Natural::from_hunks(vec![0, 1]);
+        let output = Natural::from_hunk(1) << Natural::from_hunk(64);
         assert_eq!(expected, output);
 
-        let expected = Natural::Big(vec![0, 8]);
-        let output = Natural::Small(1) << Small(64 + 3);
+        let expected = Natural::from_hunks(vec![0, 8]);
+        let output = Natural::from_hunk(1) << Natural::from_hunk(64 + 3);
         assert_eq!(expected, output);
 
-        let expected = Natural::Big(vec![0, 0b101000101]);
-        let output = Natural::Small(0b101000101) << Small(64);
+        let expected = Natural::from_hunks(vec![0, 0b101000101]);
+        let output = Natural::from_hunk(0b101000101) << Natural::from_hunk(64);
         assert_eq!(expected, output);
 
-        let expected = Natural::Big(vec![0, 0b1010001010000000]);
-        let output = Natural::Small(0b101000101) << Small(64 + 7);
+        let expected = Natural::from_hunks(vec![0, 0b1010001010000000]);
+        let output = Natural::from_hunk(0b101000101) << Natural::from_hunk(64 + 7);
         assert_eq!(expected, output);
     }
 }
+#[cfg(test)]
+mod shr_tests {
+    use crate::naturals::Hunk;
+    use crate::naturals::Natural;
+
+    #[test]
+    fn shr_inside_small() {
+        assert_eq!(
+            Natural::from_hunk(0b1011) >> Natural::from_hunk(2),
+            Natural::from_hunk(0b10)
+        );
+        assert_eq!(
+            Natural::from_hunk(Hunk::MAX) >> 0u8,
+            Natural::from_hunk(Hunk::MAX)
+        );
+        assert_eq!(
+            Natural::from_hunk(Hunk::MAX) >> Hunk::BITS,
+            Natural::from_hunk(0)
+        );
+        assert_eq!(Natural::from_hunk(1) >> u128::MAX, Natural::from_hunk(0));
+    }
+    #[test]
+    fn shr_big() {
+        let n = Natural::from_hunks(vec![0b1000, 0b101, 1]);
+        assert_eq!(
+            &n >> 3u32,
+            Natural::from_hunks(vec![(0b101 << (Hunk::BITS - 3)) | 1, 1 << (Hunk::BITS - 3)])
+        );
+        assert_eq!(&n >> Hunk::BITS, Natural::from_hunks(vec![0b101, 1]));
+        assert_eq!(&n >> (2 * Hunk::BITS), Natural::from_hunk(1));
+        assert_eq!(&n >> (3 * Hunk::BITS), Natural::from_hunk(0));
+        assert_eq!(n >> Natural::from_hunks(vec![0, 1]), Natural::from_hunk(0));
+    }
+    #[test]
+    fn shl_shr_roundtrip() {
+        let n = Natural::from_hunks(vec![0x0123_4567, Hunk::MAX, 42]);
+        for bits in [0usize, 1, 7, 63, 64, 65, 200, 1000] {
+            let shifted = &n << bits;
+            assert_eq!(&shifted >> bits, n);
+            let mut assigned = n.clone();
+            assigned <<= bits as i32;
+            assigned >>= bits as u16;
+            assert_eq!(assigned, n);
+        }
+    }
+    #[test]
+    fn shl_matches_mul() {
+        let n = Natural::from_hunks(vec![Hunk::MAX, 3]);
+        assert_eq!(&n << 5i8, n.clone() * Natural::from_hunk(32));
+        assert_eq!(
+            &n << Natural::from_hunk(Hunk::from(Hunk::BITS)),
+            n.clone() * Natural::from_hunks(vec![0, 1])
+        );
+        assert_eq!(Natural::ZERO << u128::MAX, Natural::ZERO);
+    }
+    #[test]
+    #[should_panic(expected = "negative")]
+    fn shl_negative() {
+        let _ = Natural::from_hunk(1) << -1i32;
+    }
+}
+
+impl BitAndAssign<&Natural> for Natural {
+    fn bitand_assign(&mut self, rhs: &Natural) {
+        let rhs = rhs.hunks();
+        let hunks = self.hunks_mut();
+        hunks.truncate(rhs.len());
+        hunks.iter_mut().zip(rhs).for_each(|(l, r)| *l &= r);
+        self.trim_in_place();
+    }
+}
+
+impl BitOrAssign<&Natural> for Natural {
+    fn bitor_assign(&mut self, rhs: &Natural) {
+        let rhs = rhs.hunks();
+        let hunks = self.hunks_mut();
+        if hunks.len() < rhs.len() {
+            hunks.resize(rhs.len(), 0);
+        }
+        hunks.iter_mut().zip(rhs).for_each(|(l, r)| *l |= r);
+        self.trim_in_place();
+    }
+}
+
+impl BitXorAssign<&Natural> for Natural {
+    fn bitxor_assign(&mut self, rhs: &Natural) {
+        let rhs = rhs.hunks();
+        let hunks = self.hunks_mut();
+        if hunks.len() < rhs.len() {
+            hunks.resize(rhs.len(), 0);
+        }
+        hunks.iter_mut().zip(rhs).for_each(|(l, r)| *l ^= r);
+        self.trim_in_place();
+    }
+}
+
+impl BitAnd<&Natural> for &Natural {
+    type Output = Natural;
+    fn bitand(self, rhs: &Natural) -> Natural {
+        self.hunks()
+            .iter()
+            .zip(rhs.hunks())
+            .map(|(l, r)| l & r)
+            .collect()
+    }
+}
+
+impl BitOr<&Natural> for &Natural {
+    type Output = Natural;
+    fn bitor(self, rhs: &Natural) -> Natural {
+        let (long, short) = if self.len() >= rhs.len() {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+        long.clone() | short
+    }
+}
+
+impl BitXor<&Natural> for &Natural {
+    type Output = Natural;
+    fn bitxor(self, rhs: &Natural) -> Natural {
+        let (long, short) = if self.len() >= rhs.len() {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+        long.clone() ^ short
+    }
+}
+
+forward_binop! {
+    BitAnd bitand BitAndAssign bitand_assign
+    BitOr bitor BitOrAssign bitor_assign
+    BitXor bitxor BitXorAssign bitxor_assign
+}
+
+/// Single bits, counted from the least significant bit at index 0.
+impl Natural {
+    /// The number of significant bits, `0` for zero.
+    pub(crate) fn bit_len(&self) -> usize {
+        match self.hunks() {
+            [] => 0,
+            hunks => {
+                hunks.len() * Hunk::BITS as usize - hunks[hunks.len() - 1].leading_zeros() as usize
+            }
+        }
+    }
+    pub fn bit(&self, idx: usize) -> bool {
+        self.hunks()
+            .get(idx / Hunk::BITS as usize)
+            .is_some_and(|hunk| hunk >> (idx % Hunk::BITS as usize) & 1 == 1)
+    }
+    pub fn set_bit(&mut self, idx: usize) {
+        let (hunk, bit) = (idx / Hunk::BITS as usize, idx % Hunk::BITS as usize);
+        let hunks = self.hunks_mut();
+        if hunks.len() <= hunk {
+            hunks.resize(hunk + 1, 0);
+        }
+        hunks[hunk] |= 1 << bit;
+        self.trim_in_place();
+    }
+    pub fn clear_bit(&mut self, idx: usize) {
+        let (hunk, bit) = (idx / Hunk::BITS as usize, idx % Hunk::BITS as usize);
+        if hunk < self.hunks().len() {
+            self.hunks_mut()[hunk] &= !(1 << bit);
+            self.trim_in_place();
+        }
+    }
+    pub fn flip_bit(&mut self, idx: usize) {
+        if self.bit(idx) {
+            self.clear_bit(idx);
+        } else {
+            self.set_bit(idx);
+        }
+    }
+    pub fn count_ones(&self) -> usize {
+        self.hunks()
+            .iter()
+            .map(|hunk| hunk.count_ones() as usize)
+            .sum()
+    }
+    /// Index of the lowest set bit, or `None` for zero, which has no set bit at all.
+    pub fn trailing_zeros(&self) -> Option<usize> {
+        let hunks = self.hunks();
+        let idx = hunks.iter().position(|hunk| *hunk != 0)?;
+        Some(idx * Hunk::BITS as usize + hunks[idx].trailing_zeros() as usize)
+    }
+    pub fn is_power_of_two(&self) -> bool {
+        match self.hunks().split_last() {
+            Some((top, lower)) => top.is_power_of_two() && lower.iter().all(|hunk| *hunk == 0),
+            None => false,
+        }
+    }
+    /// The smallest power of two greater than or equal to `self`. Zero gives one.
+    pub fn next_power_of_two(self) -> Natural {
+        if self.is_power_of_two() {
+            return self;
+        }
+        let mut power = Natural::ZERO;
+        power.set_bit(self.bit_len());
+        power
+    }
+}
+#[cfg(test)]
+mod bit_tests {
+    use crate::naturals::Hunk;
+    use crate::naturals::Natural;
+
+    #[test]
+    fn bitwise_operators() {
+        let lhs = Natural::from_hunks(vec![0b1100, Hunk::MAX, 1]);
+        let rhs = Natural::from_hunks(vec![0b1010, 1]);
+        assert_eq!(
+            &lhs & &rhs,
+            Natural::from_hunk(0b1000) | Natural::from_hunks(vec![0, 1])
+        );
+        assert_eq!(&lhs | &rhs, Natural::from_hunks(vec![0b1110, Hunk::MAX, 1]));
+        assert_eq!(
+            &lhs ^ &rhs,
+            Natural::from_hunks(vec![0b0110, Hunk::MAX - 1, 1])
+        );
+        assert_eq!(lhs.clone() ^ lhs.clone(), Natural::ZERO);
+        assert!((Natural::from_hunks(vec![0, 1]) & Natural::from_hunk(Hunk::MAX)).is_small());
+        let mut acc = Natural::from_hunk(0b1);
+        acc |= Natural::from_hunks(vec![0, 0, 1]);
+        acc &= &Natural::from_hunks(vec![1, 0, 0]);
+        assert_eq!(acc, Natural::from_hunk(1));
+        acc ^= Natural::from_hunk(3);
+        assert_eq!(acc, Natural::from_hunk(2));
+    }
+    #[test]
+    fn inline_and_long() {
+        let lhs = Natural::from_hunks(vec![0b1100, 3]);
+        let expected = Natural::from_hunks(vec![0b1000, 1]);
+        for len in [256, 512] {
+            let mut hunks = vec![Hunk::MAX; len];
+            hunks[..2].copy_from_slice(&[0b1010, 1]);
+            let rhs = Natural::from_hunks(hunks);
+            assert_eq!(&lhs & &rhs, expected);
+            assert_eq!(lhs.clone() & &rhs, expected);
+            assert_eq!(lhs.clone() & rhs.clone(), expected);
+            let mut acc = lhs.clone();
+            acc &= &rhs;
+            assert_eq!(acc, expected);
+        }
+    }
+    #[test]
+    fn single_bits() {
+        let mut n = Natural::ZERO;
+        n.set_bit(130);
+        assert_eq!(n, Natural::ONE << 130u32);
+        assert!(n.bit(130));
+        assert!(!n.bit(129));
+        assert!(!n.bit(10_000));
+        n.flip_bit(3);
+        assert_eq!(n.count_ones(), 2);
+        assert_eq!(n.trailing_zeros(), Some(3));
+        n.clear_bit(130);
+        assert!(n.is_small());
+        assert_eq!(n, Natural::from_hunk(8));
+        n.clear_bit(10_000);
+        n.flip_bit(3);
+        assert_eq!(n, Natural::ZERO);
+        assert_eq!(n.trailing_zeros(), None);
+    }
+    #[test]
+    fn powers_of_two() {
+        assert!(!Natural::ZERO.is_power_of_two());
+        assert!(Natural::ONE.is_power_of_two());
+        assert!(Natural::from_hunks(vec![0, 0, 4]).is_power_of_two());
+        assert!(!Natural::from_hunks(vec![1, 0, 4]).is_power_of_two());
+        assert_eq!(Natural::ZERO.next_power_of_two(), Natural::from_hunk(1));
+        assert_eq!(
+            Natural::from_hunk(5).next_power_of_two(),
+            Natural::from_hunk(8)
+        );
+        assert_eq!(
+            Natural::from_hunk(Hunk::MAX).next_power_of_two(),
+            Natural::from_hunks(vec![0, 1])
+        );
+        assert_eq!(
+            Natural::from_hunks(vec![0, 4]).next_power_of_two(),
+            Natural::from_hunks(vec![0, 4])
+        );
+        assert_eq!(
+            Natural::from_hunks(vec![1, 4]).next_power_of_two(),
+            Natural::from_hunks(vec![0, 8])
+        );
+    }
+}
@@ -0,0 +1,466 @@
+use crate::Sign;
+use crate::convert::decompose;
+use crate::error::TryFromNumberError;
+use crate::integer::Integer;
+use crate::naturals::Natural;
+use std::cmp::Ordering;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// An exact fraction of an [Integer] numerator and a nonzero [Natural] denominator.
+///
+/// The fraction is always reduced and the sign lives on the numerator, so each number has
+/// exactly one representation and zero is `0/1`. Equality and hashing compare the fields
+/// directly.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: Integer,
+    denominator: Natural,
+}
+
+impl Rational {
+    /// The fraction `numerator / denominator`, reduced. A negative denominator moves its
+    /// sign to the numerator.
+    ///
+    /// # Panics
+    /// If `denominator` is zero.
+    pub fn new(numerator: impl Into<Integer>, denominator: impl Into<Integer>) -> Self {
+        let (numerator, denominator): (Integer, Integer) = (numerator.into(), denominator.into());
+        let numerator = match denominator.sign() {
+            Some(Sign::Negative) => -numerator,
+            _ => numerator,
+        };
+        Rational::from_parts(numerator, denominator.magnitude)
+    }
+    /// Reduces `numerator / denominator` by their greatest common divisor.
+    ///
+    /// # Panics
+    /// If `denominator` is zero.
+    pub(crate) fn from_parts(mut numerator: Integer, mut denominator: Natural) -> Self {
+        assert_ne!(denominator, Natural::ZERO, "division by zero");
+        let gcd = numerator.magnitude.gcd(&denominator);
+        if gcd != Natural::ONE {
+            numerator.magnitude /= &gcd;
+            denominator /= &gcd;
+        }
+        Rational {
+            numerator,
+            denominator,
+        }
+    }
+    pub fn numerator(&self) -> &Integer {
+        &self.numerator
+    }
+    /// The denominator, which is never zero.
+    pub fn denominator(&self) -> &Natural {
+        &self.denominator
+    }
+    pub fn is_integer(&self) -> bool {
+        self.denominator == Natural::ONE
+    }
+    pub fn is_negative(&self) -> bool {
+        self.numerator.is_negative()
+    }
+}
+
+/// Constants
+impl Rational {
+    pub const ZERO: Rational = Rational {
+        numerator: Integer::ZERO,
+        denominator: Natural::ONE,
+    };
+    pub const ONE: Rational = Rational {
+        numerator: Integer::ONE,
+        denominator: Natural::ONE,
+    };
+}
+
+impl From<Integer> for Rational {
+    fn from(value: Integer) -> Self {
+        Rational {
+            numerator: value,
+            denominator: Natural::ONE,
+        }
+    }
+}
+
+impl From<Natural> for Rational {
+    fn from(value: Natural) -> Self {
+        Rational::from(Integer::from(value))
+    }
+}
+
+macro_rules! from_primitive {
+    ($($t:ty)*) => ($(
+        impl From<$t> for Rational {
+            fn from(value: $t) -> Self {
+                Rational::from(Integer::from(value))
+            }
+        }
+    )*)
+}
+
+from_primitive! { u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize }
+
+/// Every finite float is a fraction with a power of two as denominator, so the conversion is
+/// exact. Negative zero becomes zero, NaN and the infinities are an error.
+impl TryFrom<f64> for Rational {
+    type Error = TryFromNumberError;
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        if !value.is_finite() {
+            return Err(TryFromNumberError::NotFinite);
+        }
+        let (mantissa, exponent) = decompose(value.abs());
+        let sign = if value < 0.0 {
+            Sign::Negative
+        } else {
+            Sign::Positive
+        };
+        let mantissa = Natural::new(mantissa);
+        if exponent >= 0 {
+            let numerator = Integer::from_sign_magnitude(sign, mantissa << exponent);
+            return Ok(Rational::from(numerator));
+        }
+        let mut denominator = Natural::ZERO;
+        denominator.set_bit(exponent.unsigned_abs() as usize);
+        let numerator = Integer::from_sign_magnitude(sign, mantissa);
+        Ok(Rational::from_parts(numerator, denominator))
+    }
+}
+
+impl TryFrom<f32> for Rational {
+    type Error = TryFromNumberError;
+    fn try_from(value: f32) -> Result<Self, Self::Error> {
+        Rational::try_from(f64::from(value))
+    }
+}
+
+/// Rounding to the neighbouring integers.
+impl Rational {
+    /// The largest integer not above `self`.
+    pub fn floor(&self) -> Integer {
+        self.numerator
+            .div_floor(&Integer::from(self.denominator.clone()))
+    }
+    /// The smallest integer not below `self`.
+    pub fn ceil(&self) -> Integer {
+        self.numerator
+            .div_ceil(&Integer::from(self.denominator.clone()))
+    }
+    /// The integral part, rounded toward zero.
+    pub fn trunc(&self) -> Integer {
+        &self.numerator / &Integer::from(self.denominator.clone())
+    }
+    /// The nearest integer. Halfway cases round away from zero, like [f64::round].
+    pub fn round(&self) -> Integer {
+        let (quotient, remainder) = self
+            .numerator
+            .div_rem(&Integer::from(self.denominator.clone()));
+        if remainder.magnitude << 1u8 >= self.denominator {
+            quotient + self.numerator.signum()
+        } else {
+            quotient
+        }
+    }
+    /// The fractional part `self - self.trunc()`, which has the sign of `self`.
+    pub fn fract(&self) -> Rational {
+        let remainder = &self.numerator % &Integer::from(self.denominator.clone());
+        Rational {
+            numerator: remainder,
+            denominator: self.denominator.clone(),
+        }
+    }
+}
+
+impl Rational {
+    /// `1 / self`.
+    ///
+    /// # Panics
+    /// If `self` is zero.
+    pub fn recip(&self) -> Rational {
+        let sign = self.numerator.sign().expect("division by zero");
+        Rational {
+            numerator: Integer::from_sign_magnitude(sign, self.denominator.clone()),
+            denominator: self.numerator.magnitude.clone(),
+        }
+    }
+    /// `self` to the power of `exp`. Negative exponents raise the reciprocal.
+    ///
+    /// # Panics
+    /// If `self` is zero and `exp` is negative.
+    pub fn pow(self, exp: Integer) -> Rational {
+        let base = if exp.is_negative() {
+            self.recip()
+        } else {
+            self
+        };
+        let exp = exp.magnitude;
+        // Powers of coprime numbers stay coprime, so there is nothing to reduce.
+        Rational {
+            numerator: base.numerator.pow(exp.clone()),
+            denominator: base.denominator.pow(exp),
+        }
+    }
+    pub fn abs(&self) -> Rational {
+        Rational {
+            numerator: self.numerator.abs(),
+            denominator: self.denominator.clone(),
+        }
+    }
+    /// The numerator of `self` after multiplying numerator and denominator by `denominator`.
+    fn scaled(&self, denominator: &Natural) -> Integer {
+        Integer {
+            sign: self.numerator.sign,
+            magnitude: &self.numerator.magnitude * denominator,
+        }
+    }
+    /// `self + rhs`, or `self - rhs` with `subtract` set, over the common denominator.
+    fn add_fraction(&mut self, rhs: &Rational, subtract: bool) {
+        let lhs = self.scaled(&rhs.denominator);
+        let rhs_numerator = rhs.scaled(&self.denominator);
+        let numerator = if subtract {
+            lhs - rhs_numerator
+        } else {
+            lhs + rhs_numerator
+        };
+        *self = Rational::from_parts(numerator, &self.denominator * &rhs.denominator);
+    }
+}
+
+impl AddAssign<&Rational> for Rational {
+    fn add_assign(&mut self, rhs: &Rational) {
+        self.add_fraction(rhs, false);
+    }
+}
+
+impl SubAssign<&Rational> for Rational {
+    fn sub_assign(&mut self, rhs: &Rational) {
+        self.add_fraction(rhs, true);
+    }
+}
+
+impl MulAssign<&Rational> for Rational {
+    fn mul_assign(&mut self, rhs: &Rational) {
+        let numerator = &self.numerator * &rhs.numerator;
+        *self = Rational::from_parts(numerator, &self.denominator * &rhs.denominator);
+    }
+}
+
+impl DivAssign<&Rational> for Rational {
+    /// # Panics
+    /// If `rhs` is zero.
+    fn div_assign(&mut self, rhs: &Rational) {
+        let rhs = rhs.recip();
+        let numerator = &self.numerator * &rhs.numerator;
+        *self = Rational::from_parts(numerator, &self.denominator * &rhs.denominator);
+    }
+}
+
+/// Derives the owned, mixed and borrowed operator impls from `$assign<&Rational> for Rational`.
+macro_rules! forward_binop {
+    ($($imp:ident $method:ident $assign:ident $assign_method:ident)*) => ($(
+        impl $imp<Rational> for Rational {
+            type Output = Rational;
+            fn $method(mut self, rhs: Rational) -> Rational {
+                self.$assign_method(&rhs);
+                self
+            }
+        }
+        impl $imp<&Rational> for Rational {
+            type Output = Rational;
+            fn $method(mut self, rhs: &Rational) -> Rational {
+                self.$assign_method(rhs);
+                self
+            }
+        }
+        impl $imp<Rational> for &Rational {
+            type Output = Rational;
+            fn $method(self, rhs: Rational) -> Rational {
+                self.clone().$method(&rhs)
+            }
+        }
+        impl $imp<&Rational> for &Rational {
+            type Output = Rational;
+            fn $method(self, rhs: &Rational) -> Rational {
+                self.clone().$method(rhs)
+            }
+        }
+        impl $assign<Rational> for Rational {
+            fn $assign_method(&mut self, rhs: Rational) {
+                self.$assign_method(&rhs);
+            }
+        }
+    )*)
+}
+
+forward_binop! {
+    Add add AddAssign add_assign
+    Sub sub SubAssign sub_assign
+    Mul mul MulAssign mul_assign
+    Div div DivAssign div_assign
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+    fn neg(mut self) -> Rational {
+        self.numerator = -self.numerator;
+        self
+    }
+}
+
+impl Neg for &Rational {
+    type Output = Rational;
+    fn neg(self) -> Rational {
+        -self.clone()
+    }
+}
+
+/// Compares `a/b` and `c/d` as `a·d` and `c·b`, which is valid as denominators are positive.
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.denominator == other.denominator {
+            return self.numerator.cmp(&other.numerator);
+        }
+        self.scaled(&other.denominator)
+            .cmp(&other.scaled(&self.denominator))
+    }
+}
+
+impl PartialOrd<Self> for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::TryFromNumberError;
+    use crate::integer::Integer;
+    use crate::naturals::Natural;
+    use crate::rational::Rational;
+    use std::collections::HashSet;
+
+    fn q(numerator: i64, denominator: i64) -> Rational {
+        Rational::new(numerator, denominator)
+    }
+
+    #[test]
+    fn reduced() {
+        let half = q(6, 12);
+        assert_eq!(half.numerator(), &Integer::new(1u8));
+        assert_eq!(half.denominator(), &Natural::new(2u8));
+        assert_eq!(q(3, -6), q(-1, 2));
+        assert_eq!(q(-3, -6), half);
+        assert_eq!(q(0, -5), Rational::ZERO);
+        assert_eq!(Rational::ZERO.denominator(), &Natural::ONE);
+        let set: HashSet<Rational> = [q(1, 3), q(2, 6), q(-4, -12)].into_iter().collect();
+        assert_eq!(set.len(), 1);
+    }
+    #[test]
+    #[should_panic(expected = "division by zero")]
+    fn zero_denominator() {
+        let _ = q(1, 0);
+    }
+    #[test]
+    fn arithmetic() {
+        assert_eq!(q(1, 2) + q(1, 3), q(5, 6));
+        assert_eq!(q(1, 2) - q(5, 6), q(-1, 3));
+        assert_eq!(q(1, 6) + q(1, 3), q(1, 2));
+        assert_eq!(q(-2, 3) * q(9, 4), q(-3, 2));
+        assert_eq!(q(-2, 3) / q(-4, 9), q(3, 2));
+        assert_eq!(q(3, 4) - q(3, 4), Rational::ZERO);
+        assert_eq!(-q(3, 4), q(-3, 4));
+        let mut sum = Rational::ZERO;
+        for n in 1..=20 {
+            sum += q(1, n * (n + 1));
+        }
+        assert_eq!(sum, q(20, 21));
+        let mut n = q(7, 3);
+        n *= q(3, 7);
+        n -= Rational::ONE;
+        n /= q(5, 1);
+        assert_eq!(n, Rational::ZERO);
+    }
+    #[test]
+    #[should_panic(expected = "division by zero")]
+    fn divide_by_zero() {
+        let _ = Rational::ONE / Rational::ZERO;
+    }
+    #[test]
+    fn ordering() {
+        let mut values = vec![
+            q(1, 2),
+            q(-1, 3),
+            q(2, 3),
+            Rational::ZERO,
+            q(-1, 2),
+            q(7, 1),
+        ];
+        values.sort();
+        assert_eq!(
+            values,
+            [
+                q(-1, 2),
+                q(-1, 3),
+                Rational::ZERO,
+                q(1, 2),
+                q(2, 3),
+                q(7, 1)
+            ]
+        );
+        assert!(q(1, 3) < q(1, 2));
+        assert!(q(-1, 3) > q(-1, 2));
+    }
+    #[test]
+    fn recip_and_pow() {
+        assert_eq!(q(-3, 4).recip(), q(-4, 3));
+        assert_eq!(q(2, 3).pow(Integer::new(3u8)), q(8, 27));
+        assert_eq!(q(-2, 3).pow(Integer::new(-3i8)), q(-27, 8));
+        assert_eq!(q(-2, 3).pow(Integer::ZERO), Rational::ONE);
+        assert_eq!(Rational::ZERO.pow(Integer::new(2u8)), Rational::ZERO);
+    }
+    #[test]
+    fn rounding() {
+        let cases = [
+            (q(7, 2), 3, 4, 3, 4),
+            (q(-7, 2), -4, -3, -3, -4),
+            (q(5, 3), 1, 2, 1, 2),
+            (q(-5, 3), -2, -1, -1, -2),
+            (q(4, 3), 1, 2, 1, 1),
+            (q(-4, 3), -2, -1, -1, -1),
+            (q(6, 1), 6, 6, 6, 6),
+            (Rational::ZERO, 0, 0, 0, 0),
+        ];
+        for (n, floor, ceil, trunc, round) in cases {
+            assert_eq!(n.floor(), Integer::new(floor), "floor {n}");
+            assert_eq!(n.ceil(), Integer::new(ceil), "ceil {n}");
+            assert_eq!(n.trunc(), Integer::new(trunc), "trunc {n}");
+            assert_eq!(n.round(), Integer::new(round), "round {n}");
+            assert_eq!(Rational::from(n.trunc()) + n.fract(), n);
+        }
+    }
+    #[test]
+    fn from_floats() {
+        assert_eq!(Rational::try_from(0.75), Ok(q(3, 4)));
+        assert_eq!(Rational::try_from(-0.1f32), Ok(q(-13421773, 134217728)));
+        assert_eq!(Rational::try_from(-0.0), Ok(Rational::ZERO));
+        assert_eq!(
+            Rational::try_from(1e20),
+            Ok(Rational::from(100_000_000_000_000_000_000u128))
+        );
+        let tiny = Rational::try_from(f64::from_bits(1)).unwrap();
+        assert_eq!(tiny.numerator(), &Integer::ONE);
+        assert_eq!(tiny.denominator(), &(Natural::ONE << 1074u16));
+        let third = Rational::try_from(1.0 / 3.0).unwrap();
+        assert!(third < q(1, 3) && third > q(333, 1000));
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert_eq!(
+                Rational::try_from(value),
+                Err(TryFromNumberError::NotFinite)
+            );
+        }
+        assert_eq!(
+            Rational::try_from(f32::NAN),
+            Err(TryFromNumberError::NotFinite)
+        );
+    }
+}
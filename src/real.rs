@@ -1 +1,845 @@
-pub struct Real;
\ No newline at end of file
+//! Binary floating point numbers of arbitrary precision, in the style of MPFR.
+//!
+//! A [Float] is `±mantissa · 2^exponent` with a [Natural] mantissa of exactly `precision`
+//! bits, or one of the special values NaN, ±infinity and ±0. Every operation computes the
+//! exact result, or enough of it to decide the rounding, and then rounds once, so results
+//! are correctly rounded in every [Round] mode. The exponent is an `i64` and has no
+//! artificial range, so there is no overflow to infinity and no underflow to zero.
+
+use crate::Sign;
+use crate::convert::decompose;
+use crate::integer::Integer;
+use crate::naturals::Natural;
+use crate::rational::Rational;
+use std::cmp::Ordering;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// How a result that does not fit into the target precision is rounded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Round {
+    /// To the nearest representable value, ties to the one with an even mantissa.
+    NearestEven,
+    /// Toward zero, which cuts off the extra bits.
+    TowardZero,
+    /// Toward positive infinity.
+    Up,
+    /// Toward negative infinity.
+    Down,
+}
+
+#[derive(Debug, Clone)]
+enum Value {
+    Nan,
+    Infinite,
+    Zero,
+    /// `mantissa · 2^exponent`, where the mantissa has exactly `precision` bits.
+    Finite {
+        mantissa: Natural,
+        exponent: i64,
+    },
+}
+
+/// A binary floating point number with a precision of its own. See the [module](self) docs.
+///
+/// Comparisons follow IEEE 754: NaN is unordered and unequal to itself, and `-0 == +0`.
+/// Values of different precisions compare by their exact values.
+#[derive(Debug, Clone)]
+pub struct Float {
+    sign: Sign,
+    value: Value,
+    precision: usize,
+}
+
+/// Special values and properties
+impl Float {
+    fn special(sign: Sign, value: Value, precision: usize) -> Float {
+        assert!(precision > 0, "precision must be at least one bit");
+        Float {
+            sign,
+            value,
+            precision,
+        }
+    }
+    pub fn nan(precision: usize) -> Float {
+        Float::special(Sign::Positive, Value::Nan, precision)
+    }
+    pub fn infinity(sign: Sign, precision: usize) -> Float {
+        Float::special(sign, Value::Infinite, precision)
+    }
+    pub fn zero(sign: Sign, precision: usize) -> Float {
+        Float::special(sign, Value::Zero, precision)
+    }
+    /// The number of mantissa bits.
+    pub fn precision(&self) -> usize {
+        self.precision
+    }
+    pub fn is_nan(&self) -> bool {
+        matches!(self.value, Value::Nan)
+    }
+    pub fn is_infinite(&self) -> bool {
+        matches!(self.value, Value::Infinite)
+    }
+    pub fn is_zero(&self) -> bool {
+        matches!(self.value, Value::Zero)
+    }
+    /// Whether `self` is neither NaN nor infinite.
+    pub fn is_finite(&self) -> bool {
+        matches!(self.value, Value::Zero | Value::Finite { .. })
+    }
+    /// The sign bit, which is also set for `-0` and `-∞`. NaN is never negative.
+    pub fn is_sign_negative(&self) -> bool {
+        self.sign == Sign::Negative && !self.is_nan()
+    }
+    /// `self` with a positive sign. This is exact and keeps the precision.
+    pub fn abs(&self) -> Float {
+        Float {
+            sign: Sign::Positive,
+            ..self.clone()
+        }
+    }
+}
+
+/// Rounds `sign · (mantissa + tail) · 2^exponent` to `precision` bits. The `tail` is unknown
+/// but lies strictly between 0 and 1 if `inexact` is set, and is 0 otherwise. Inexact
+/// mantissas need at least `precision + 2` bits, so the tail only ever acts as a sticky bit.
+fn rounded(
+    sign: Sign,
+    mantissa: Natural,
+    exponent: i64,
+    inexact: bool,
+    precision: usize,
+    round: Round,
+) -> Float {
+    let len = mantissa.bit_len();
+    if len == 0 {
+        return Float::zero(sign, precision);
+    }
+    if len <= precision {
+        debug_assert!(!inexact, "an inexact mantissa is too short to round");
+        let shift = precision - len;
+        let value = Value::Finite {
+            mantissa: mantissa << shift,
+            exponent: exponent - shift as i64,
+        };
+        return Float::special(sign, value, precision);
+    }
+    let shift = len - precision;
+    let half = mantissa.bit(shift - 1);
+    let sticky = inexact
+        || mantissa
+            .trailing_zeros()
+            .is_some_and(|zeros| zeros < shift - 1);
+    let mut kept = mantissa >> shift;
+    let up = match round {
+        Round::NearestEven => half && (sticky || kept.bit(0)),
+        Round::TowardZero => false,
+        Round::Up => (half || sticky) && sign == Sign::Positive,
+        Round::Down => (half || sticky) && sign == Sign::Negative,
+    };
+    let mut exponent = exponent + shift as i64;
+    if up {
+        kept += &Natural::ONE;
+        if kept.bit_len() > precision {
+            kept >>= 1u8;
+            exponent += 1;
+        }
+    }
+    let value = Value::Finite {
+        mantissa: kept,
+        exponent,
+    };
+    Float::special(sign, value, precision)
+}
+
+/// The sign of a sum of opposite values that cancels exactly. It is positive, except when
+/// rounding toward negative infinity.
+fn exact_zero_sign(round: Round) -> Sign {
+    match round {
+        Round::Down => Sign::Negative,
+        _ => Sign::Positive,
+    }
+}
+
+/// The exponent just above the most significant bit of `mantissa · 2^exponent`.
+fn top(mantissa: &Natural, exponent: i64) -> i64 {
+    exponent + mantissa.bit_len() as i64
+}
+
+/// Conversions
+impl Float {
+    /// `value` rounded to `precision` bits. Every `f64` is exact from 53 bits on.
+    pub fn from_f64(value: f64, precision: usize, round: Round) -> Float {
+        let sign = if value.is_sign_negative() {
+            Sign::Negative
+        } else {
+            Sign::Positive
+        };
+        if value.is_nan() {
+            Float::nan(precision)
+        } else if value.is_infinite() {
+            Float::infinity(sign, precision)
+        } else if value == 0.0 {
+            Float::zero(sign, precision)
+        } else {
+            let (mantissa, exponent) = decompose(value.abs());
+            let mantissa = Natural::new(mantissa);
+            rounded(sign, mantissa, exponent.into(), false, precision, round)
+        }
+    }
+    /// `value` rounded to `precision` bits. Zero becomes `+0`.
+    pub fn from_integer(value: &Integer, precision: usize, round: Round) -> Float {
+        let sign = value.sign().unwrap_or(Sign::Positive);
+        rounded(sign, value.magnitude().clone(), 0, false, precision, round)
+    }
+    /// `value` rounded to `precision` bits, with one long division. Zero becomes `+0`.
+    pub fn from_rational(value: &Rational, precision: usize, round: Round) -> Float {
+        let sign = value.numerator().sign().unwrap_or(Sign::Positive);
+        let (numerator, denominator) = (value.numerator().magnitude(), value.denominator());
+        let shift = (precision + 2 + denominator.bit_len()).saturating_sub(numerator.bit_len());
+        let (quotient, remainder) = (numerator << shift).div_rem(denominator);
+        let inexact = remainder != Natural::ZERO;
+        rounded(sign, quotient, -(shift as i64), inexact, precision, round)
+    }
+    /// The exact value, or `None` for NaN and the infinities.
+    pub fn to_rational(&self) -> Option<Rational> {
+        match &self.value {
+            Value::Nan | Value::Infinite => None,
+            Value::Zero => Some(Rational::ZERO),
+            Value::Finite { mantissa, exponent } => {
+                let numerator = Integer::from_sign_magnitude(self.sign, mantissa.clone());
+                Some(if *exponent >= 0 {
+                    Rational::from(numerator << *exponent)
+                } else {
+                    let mut denominator = Natural::ZERO;
+                    denominator.set_bit(exponent.unsigned_abs() as usize);
+                    Rational::from_parts(numerator, denominator)
+                })
+            }
+        }
+    }
+    /// The nearest `f64`, with ties to even, including subnormal results. Values beyond
+    /// the `f64` range become infinite.
+    pub fn to_f64(&self) -> f64 {
+        let magnitude = match &self.value {
+            Value::Nan => return f64::NAN,
+            Value::Infinite => f64::INFINITY,
+            Value::Zero => 0.0,
+            Value::Finite { mantissa, exponent } => {
+                let top = top(mantissa, *exponent);
+                // The lowest bit an f64 keeps at this magnitude is never below 2^-1074.
+                let bits = (top + 1074).min(53);
+                if top > 1024 {
+                    f64::INFINITY
+                } else if bits < 0 {
+                    0.0
+                } else if bits == 0 {
+                    // Between 2^-1075 and 2^-1074, where exactly 2^-1075 ties to zero.
+                    if mantissa.is_power_of_two() {
+                        0.0
+                    } else {
+                        f64::from_bits(1)
+                    }
+                } else {
+                    let rounded = rounded(
+                        Sign::Positive,
+                        mantissa.clone(),
+                        *exponent,
+                        false,
+                        bits as usize,
+                        Round::NearestEven,
+                    );
+                    let Value::Finite { mantissa, exponent } = rounded.value else {
+                        unreachable!("rounding a nonzero value keeps it finite")
+                    };
+                    // The mantissa fits and the scaling in two halves is exact, even for
+                    // subnormal results.
+                    let half = exponent / 2;
+                    mantissa.to_f64() * 2f64.powi(half as i32) * 2f64.powi((exponent - half) as i32)
+                }
+            }
+        };
+        match self.sign {
+            Sign::Negative => -magnitude,
+            Sign::Positive => magnitude,
+        }
+    }
+    /// `self` rounded to another precision.
+    pub fn with_precision(&self, precision: usize, round: Round) -> Float {
+        match &self.value {
+            Value::Finite { mantissa, exponent } => rounded(
+                self.sign,
+                mantissa.clone(),
+                *exponent,
+                false,
+                precision,
+                round,
+            ),
+            value => Float::special(self.sign, value.clone(), precision),
+        }
+    }
+}
+
+/// Correctly rounded arithmetic into a result of the given precision. Special values are
+/// handled like IEEE 754 and MPFR do.
+impl Float {
+    pub fn add_round(&self, rhs: &Float, precision: usize, round: Round) -> Float {
+        self.add_signed(rhs, rhs.sign, precision, round)
+    }
+    pub fn sub_round(&self, rhs: &Float, precision: usize, round: Round) -> Float {
+        self.add_signed(rhs, -rhs.sign, precision, round)
+    }
+    /// `self + rhs`, with `rhs_sign` in place of the sign of `rhs`.
+    fn add_signed(&self, rhs: &Float, rhs_sign: Sign, precision: usize, round: Round) -> Float {
+        match (&self.value, &rhs.value) {
+            (Value::Nan, _) | (_, Value::Nan) => Float::nan(precision),
+            (Value::Infinite, Value::Infinite) if self.sign != rhs_sign => Float::nan(precision),
+            (Value::Infinite, _) => Float::infinity(self.sign, precision),
+            (_, Value::Infinite) => Float::infinity(rhs_sign, precision),
+            (Value::Zero, Value::Zero) if self.sign == rhs_sign => Float::zero(rhs_sign, precision),
+            (Value::Zero, Value::Zero) => Float::zero(exact_zero_sign(round), precision),
+            (Value::Zero, Value::Finite { mantissa, exponent }) => rounded(
+                rhs_sign,
+                mantissa.clone(),
+                *exponent,
+                false,
+                precision,
+                round,
+            ),
+            (Value::Finite { mantissa, exponent }, Value::Zero) => rounded(
+                self.sign,
+                mantissa.clone(),
+                *exponent,
+                false,
+                precision,
+                round,
+            ),
+            (
+                Value::Finite {
+                    mantissa: lhs,
+                    exponent: lhs_exponent,
+                },
+                Value::Finite {
+                    mantissa: rhs,
+                    exponent: rhs_exponent,
+                },
+            ) => add_finite(
+                (self.sign, lhs, *lhs_exponent),
+                (rhs_sign, rhs, *rhs_exponent),
+                precision,
+                round,
+            ),
+        }
+    }
+    pub fn mul_round(&self, rhs: &Float, precision: usize, round: Round) -> Float {
+        let sign = self.sign * rhs.sign;
+        match (&self.value, &rhs.value) {
+            (Value::Nan, _) | (_, Value::Nan) => Float::nan(precision),
+            (Value::Infinite, Value::Zero) | (Value::Zero, Value::Infinite) => {
+                Float::nan(precision)
+            }
+            (Value::Infinite, _) | (_, Value::Infinite) => Float::infinity(sign, precision),
+            (Value::Zero, _) | (_, Value::Zero) => Float::zero(sign, precision),
+            (
+                Value::Finite {
+                    mantissa: lhs,
+                    exponent: lhs_exponent,
+                },
+                Value::Finite {
+                    mantissa: rhs,
+                    exponent: rhs_exponent,
+                },
+            ) => rounded(
+                sign,
+                lhs * rhs,
+                lhs_exponent + rhs_exponent,
+                false,
+                precision,
+                round,
+            ),
+        }
+    }
+    /// Division by zero gives a signed infinity, `0 / 0` is NaN.
+    pub fn div_round(&self, rhs: &Float, precision: usize, round: Round) -> Float {
+        let sign = self.sign * rhs.sign;
+        match (&self.value, &rhs.value) {
+            (Value::Nan, _) | (_, Value::Nan) => Float::nan(precision),
+            (Value::Infinite, Value::Infinite) | (Value::Zero, Value::Zero) => {
+                Float::nan(precision)
+            }
+            (Value::Infinite, _) | (_, Value::Zero) => Float::infinity(sign, precision),
+            (Value::Zero, _) | (_, Value::Infinite) => Float::zero(sign, precision),
+            (
+                Value::Finite {
+                    mantissa: lhs,
+                    exponent: lhs_exponent,
+                },
+                Value::Finite {
+                    mantissa: rhs,
+                    exponent: rhs_exponent,
+                },
+            ) => {
+                // Enough quotient bits for the rounding, the remainder decides the sticky bit.
+                let shift = (precision + 2 + rhs.bit_len()).saturating_sub(lhs.bit_len());
+                let (quotient, remainder) = (lhs << shift).div_rem(rhs);
+                let exponent = lhs_exponent - rhs_exponent - shift as i64;
+                let inexact = remainder != Natural::ZERO;
+                rounded(sign, quotient, exponent, inexact, precision, round)
+            }
+        }
+    }
+    /// The square root. `sqrt(-0)` is `-0`, every other negative value gives NaN.
+    pub fn sqrt_round(&self, precision: usize, round: Round) -> Float {
+        match (&self.value, self.sign) {
+            (Value::Nan, _) => Float::nan(precision),
+            (Value::Zero, sign) => Float::zero(sign, precision),
+            (_, Sign::Negative) => Float::nan(precision),
+            (Value::Infinite, sign) => Float::infinity(sign, precision),
+            (Value::Finite { mantissa, exponent }, sign) => {
+                // At least `2 · (precision + 2)` bits and an even exponent for the radicand.
+                let mut shift = (2 * (precision + 2)).saturating_sub(mantissa.bit_len());
+                if (exponent - shift as i64).rem_euclid(2) == 1 {
+                    shift += 1;
+                }
+                let radicand = mantissa << shift;
+                let root = radicand.isqrt();
+                let inexact = &root * &root != radicand;
+                let exponent = (exponent - shift as i64) / 2;
+                rounded(sign, root, exponent, inexact, precision, round)
+            }
+        }
+    }
+    /// The square root in the precision of `self`, rounded to nearest.
+    pub fn sqrt(&self) -> Float {
+        self.sqrt_round(self.precision, Round::NearestEven)
+    }
+}
+
+/// The exact sum of two finite values, rounded.
+///
+/// If the smaller operand lies entirely below the rounding position of the larger one, it
+/// only matters as a sticky bit. It is then replaced by a single bit just below both that
+/// position and the lowest bit of the larger operand, which rounds the same way and keeps
+/// the alignment shift short.
+fn add_finite(
+    lhs: (Sign, &Natural, i64),
+    rhs: (Sign, &Natural, i64),
+    precision: usize,
+    round: Round,
+) -> Float {
+    let ((big_sign, big, big_exponent), (small_sign, small, small_exponent)) =
+        if top(lhs.1, lhs.2) >= top(rhs.1, rhs.2) {
+            (lhs, rhs)
+        } else {
+            (rhs, lhs)
+        };
+    let floor = big_exponent.min(top(big, big_exponent) - precision as i64 - 2);
+    let (small, small_exponent) = if top(small, small_exponent) <= floor {
+        (Natural::ONE, floor - 1)
+    } else {
+        (small.clone(), small_exponent)
+    };
+    let exponent = big_exponent.min(small_exponent);
+    let mut big = big << (big_exponent - exponent) as usize;
+    let mut small = small << (small_exponent - exponent) as usize;
+    if big_sign == small_sign {
+        big += &small;
+        return rounded(big_sign, big, exponent, false, precision, round);
+    }
+    match big.cmp(&small) {
+        Ordering::Equal => Float::zero(exact_zero_sign(round), precision),
+        Ordering::Greater => {
+            big -= &small;
+            rounded(big_sign, big, exponent, false, precision, round)
+        }
+        Ordering::Less => {
+            small -= &big;
+            rounded(small_sign, small, exponent, false, precision, round)
+        }
+    }
+}
+
+/// The operators round to nearest, into the larger of the two precisions.
+macro_rules! impl_binop {
+    ($($imp:ident $method:ident $round:ident)*) => ($(
+        impl $imp<&Float> for &Float {
+            type Output = Float;
+            fn $method(self, rhs: &Float) -> Float {
+                self.$round(rhs, self.precision.max(rhs.precision), Round::NearestEven)
+            }
+        }
+        impl $imp<Float> for Float {
+            type Output = Float;
+            fn $method(self, rhs: Float) -> Float {
+                (&self).$method(&rhs)
+            }
+        }
+        impl $imp<&Float> for Float {
+            type Output = Float;
+            fn $method(self, rhs: &Float) -> Float {
+                (&self).$method(rhs)
+            }
+        }
+        impl $imp<Float> for &Float {
+            type Output = Float;
+            fn $method(self, rhs: Float) -> Float {
+                self.$method(&rhs)
+            }
+        }
+    )*)
+}
+
+impl_binop! {
+    Add add add_round
+    Sub sub sub_round
+    Mul mul mul_round
+    Div div div_round
+}
+
+/// Flips the sign, which is exact. NaN stays NaN.
+impl Neg for Float {
+    type Output = Float;
+    fn neg(mut self) -> Float {
+        self.sign = -self.sign;
+        self
+    }
+}
+
+impl Neg for &Float {
+    type Output = Float;
+    fn neg(self) -> Float {
+        -self.clone()
+    }
+}
+
+impl PartialEq<Self> for Float {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd<Self> for Float {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let signum = |float: &Float| match (&float.value, float.sign) {
+            (Value::Zero, _) => 0,
+            (_, Sign::Negative) => -1,
+            (_, Sign::Positive) => 1,
+        };
+        let magnitude = match (&self.value, &other.value) {
+            (Value::Nan, _) | (_, Value::Nan) => return None,
+            _ if signum(self) != signum(other) => return Some(signum(self).cmp(&signum(other))),
+            (Value::Zero, Value::Zero) | (Value::Infinite, Value::Infinite) => Ordering::Equal,
+            (Value::Infinite, _) => Ordering::Greater,
+            (_, Value::Infinite) => Ordering::Less,
+            (
+                Value::Finite {
+                    mantissa: lhs,
+                    exponent: lhs_exponent,
+                },
+                Value::Finite {
+                    mantissa: rhs,
+                    exponent: rhs_exponent,
+                },
+            ) => top(lhs, *lhs_exponent)
+                .cmp(&top(rhs, *rhs_exponent))
+                .then_with(|| {
+                    // Same top bit, so the shorter mantissa is aligned to the longer one.
+                    let (lhs_len, rhs_len) = (lhs.bit_len(), rhs.bit_len());
+                    if lhs_len >= rhs_len {
+                        lhs.cmp(&(rhs << (lhs_len - rhs_len)))
+                    } else {
+                        (lhs << (rhs_len - lhs_len)).cmp(rhs)
+                    }
+                }),
+            (Value::Zero, _) | (_, Value::Zero) => {
+                unreachable!("zero has a different signum than nonzero values")
+            }
+        };
+        Some(match self.sign {
+            Sign::Negative => magnitude.reverse(),
+            Sign::Positive => magnitude,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Sign;
+    use crate::integer::Integer;
+    use crate::naturals::Natural;
+    use crate::rational::Rational;
+    use crate::real::{Float, Round, Value};
+    use std::cmp::Ordering;
+
+    const MODES: [Round; 4] = [
+        Round::NearestEven,
+        Round::TowardZero,
+        Round::Up,
+        Round::Down,
+    ];
+
+    /// Deterministic normal doubles of mixed signs, between `2^-100` and `2^100` in magnitude.
+    fn doubles() -> Vec<f64> {
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        std::iter::repeat_with(move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let exponent = (state >> 52) % 200 + 1023 - 100;
+            f64::from_bits(state & !(0x7ff << 52) | exponent << 52)
+        })
+        .take(200)
+        .collect()
+    }
+
+    fn exact(value: f64) -> Float {
+        Float::from_f64(value, 53, Round::NearestEven)
+    }
+
+    fn power_of_two(exponent: i64) -> Rational {
+        let mut power = Natural::ZERO;
+        power.set_bit(exponent.unsigned_abs() as usize);
+        if exponent >= 0 {
+            Rational::from(power)
+        } else {
+            Rational::ONE / Rational::from(power)
+        }
+    }
+
+    /// The next representable value above the nonzero `float`, in its precision.
+    fn next_up(float: &Float) -> Rational {
+        let Value::Finite { mantissa, exponent } = &float.value else {
+            panic!("{float:?} has no finite successor");
+        };
+        let value = float.to_rational().unwrap();
+        let lowest = Natural::ONE << (float.precision - 1);
+        if float.sign == Sign::Negative && *mantissa == lowest {
+            value + power_of_two(exponent - 1)
+        } else {
+            value + power_of_two(*exponent)
+        }
+    }
+
+    /// Checks the results of one operation in the order of [MODES]. `cmp` compares a
+    /// candidate against the exact result, which does not have to be rational.
+    fn assert_correctly_rounded(cmp: impl Fn(&Rational) -> Ordering, results: [Float; 4]) {
+        let [nearest, toward_zero, up, down] = results;
+        let low = down.to_rational().unwrap();
+        let is_exact = cmp(&low) == Ordering::Equal;
+        assert_ne!(cmp(&low), Ordering::Greater, "rounding down went up");
+        if is_exact {
+            for result in [&nearest, &toward_zero, &up] {
+                assert_eq!(result.to_rational(), Some(low.clone()));
+            }
+            return;
+        }
+        let high = next_up(&down);
+        assert_eq!(
+            cmp(&high),
+            Ordering::Greater,
+            "rounding down is not the closest"
+        );
+        assert_eq!(up.to_rational(), Some(high.clone()));
+        let toward = if low.is_negative() { &high } else { &low };
+        assert_eq!(toward_zero.to_rational().as_ref(), Some(toward));
+        let nearest = nearest.to_rational().unwrap();
+        match cmp(&((low.clone() + &high) / Rational::from(2u8))) {
+            Ordering::Greater => assert_eq!(nearest, low),
+            Ordering::Less => assert_eq!(nearest, high),
+            Ordering::Equal => assert!(nearest == low || nearest == high),
+        }
+    }
+
+    #[test]
+    fn matches_f64() {
+        for pair in doubles().windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            let (x, y) = (exact(a), exact(b));
+            assert_eq!((&x + &y).to_f64(), a + b, "{a} + {b}");
+            assert_eq!((&x - &y).to_f64(), a - b, "{a} - {b}");
+            assert_eq!((&x * &y).to_f64(), a * b, "{a} * {b}");
+            assert_eq!((&x / &y).to_f64(), a / b, "{a} / {b}");
+            if a < 0.0 {
+                assert!(x.sqrt().is_nan());
+            } else {
+                assert_eq!(x.sqrt().to_f64(), a.sqrt(), "sqrt {a}");
+            }
+            assert_eq!(x.partial_cmp(&y), a.partial_cmp(&b));
+        }
+    }
+    #[test]
+    fn all_modes_and_precisions() {
+        let values = doubles();
+        for precision in [1, 2, 5, 24, 53, 64, 100, 131] {
+            for pair in values.windows(2).take(40) {
+                let (x, y) = (exact(pair[0]), exact(pair[1]));
+                let (a, b) = (x.to_rational().unwrap(), y.to_rational().unwrap());
+                let results = |op: fn(&Float, &Float, usize, Round) -> Float| {
+                    MODES.map(|round| op(&x, &y, precision, round))
+                };
+                let sum = a.clone() + &b;
+                assert_correctly_rounded(|c| c.cmp(&sum), results(Float::add_round));
+                let difference = a.clone() - &b;
+                assert_correctly_rounded(|c| c.cmp(&difference), results(Float::sub_round));
+                let product = a.clone() * &b;
+                assert_correctly_rounded(|c| c.cmp(&product), results(Float::mul_round));
+                let quotient = a.clone() / &b;
+                assert_correctly_rounded(|c| c.cmp(&quotient), results(Float::div_round));
+                let radicand = a.abs();
+                assert_correctly_rounded(
+                    |c| (c.clone() * c).cmp(&radicand),
+                    MODES.map(|round| x.abs().sqrt_round(precision, round)),
+                );
+                assert_correctly_rounded(
+                    |c| c.cmp(&quotient),
+                    MODES.map(|round| Float::from_rational(&quotient, precision, round)),
+                );
+            }
+        }
+    }
+    #[test]
+    fn far_apart_operands() {
+        let one = Float::from_integer(&Integer::ONE, 10, Round::NearestEven);
+        let tiny = Float::from_rational(&power_of_two(-10_000), 10, Round::NearestEven);
+        let sum = |round| one.add_round(&tiny, 10, round).to_rational().unwrap();
+        assert_eq!(sum(Round::NearestEven), Rational::ONE);
+        assert_eq!(sum(Round::Down), Rational::ONE);
+        assert_eq!(sum(Round::Up), Rational::ONE + power_of_two(-9));
+        let difference = |round| one.sub_round(&tiny, 10, round).to_rational().unwrap();
+        assert_eq!(difference(Round::NearestEven), Rational::ONE);
+        assert_eq!(difference(Round::Up), Rational::ONE);
+        assert_eq!(
+            difference(Round::TowardZero),
+            Rational::ONE - power_of_two(-10)
+        );
+        let sum = tiny.add_round(&one, 20_000, Round::NearestEven);
+        assert_eq!(
+            sum.to_rational(),
+            Some(Rational::ONE + power_of_two(-10_000))
+        );
+    }
+    #[test]
+    fn high_precision() {
+        let two = Float::from_integer(&Integer::new(2u8), 300, Round::NearestEven);
+        for precision in [200, 300, 1000] {
+            let root = two
+                .sqrt_round(precision, Round::Down)
+                .to_rational()
+                .unwrap();
+            assert!(root.clone() * &root < Rational::from(2u8));
+            assert_correctly_rounded(
+                |c| (c.clone() * c).cmp(&Rational::from(2u8)),
+                MODES.map(|round| two.sqrt_round(precision, round)),
+            );
+        }
+        let third = Float::from_rational(&Rational::new(1u8, 3u8), 200, Round::NearestEven);
+        let one = &third * Float::from_integer(&Integer::new(3u8), 200, Round::NearestEven);
+        assert_eq!(one, Float::from_f64(1.0, 2, Round::NearestEven));
+        assert_eq!(third.to_f64(), 1.0 / 3.0);
+    }
+    #[test]
+    fn special_values() {
+        let p = 20;
+        let nan = Float::nan(p);
+        let inf = Float::infinity(Sign::Positive, p);
+        let zero = Float::zero(Sign::Positive, p);
+        let neg_zero = Float::zero(Sign::Negative, p);
+        let one = Float::from_f64(1.0, p, Round::NearestEven);
+        assert!((&nan + &one).is_nan());
+        assert!((&inf - &inf).is_nan());
+        assert!((&inf * &zero).is_nan());
+        assert!((&zero / &neg_zero).is_nan());
+        assert!((&inf / &inf).is_nan());
+        assert!((-&one).sqrt().is_nan());
+        assert_eq!(&inf + &one, inf);
+        assert_eq!(&one / &neg_zero, -&inf);
+        assert_eq!(&one / &inf, zero);
+        assert!((&neg_zero * &one).is_sign_negative());
+        assert!((&neg_zero + &neg_zero).is_sign_negative());
+        assert!(!(&neg_zero + &zero).is_sign_negative());
+        assert!(neg_zero.add_round(&zero, p, Round::Down).is_sign_negative());
+        assert!(!(&one - &one).is_sign_negative());
+        assert!(one.sub_round(&one, p, Round::Down).is_sign_negative());
+        assert!(neg_zero.sqrt().is_sign_negative() && neg_zero.sqrt().is_zero());
+        assert_eq!(inf.sqrt(), inf);
+        assert!(!nan.is_sign_negative() && !(-&nan).is_sign_negative());
+        assert_eq!(nan.to_rational(), None);
+        assert!(nan.to_f64().is_nan());
+        assert_eq!((-&inf).to_f64(), f64::NEG_INFINITY);
+        assert_eq!(neg_zero.to_f64().to_bits(), (-0.0f64).to_bits());
+    }
+    #[test]
+    fn comparisons() {
+        let nan = Float::nan(10);
+        assert!(nan != nan);
+        assert_eq!(nan.partial_cmp(&Float::zero(Sign::Positive, 10)), None);
+        assert_eq!(
+            Float::zero(Sign::Negative, 5),
+            Float::zero(Sign::Positive, 50)
+        );
+        let coarse = Float::from_f64(1.5, 2, Round::NearestEven);
+        let fine = Float::from_f64(1.5, 200, Round::NearestEven);
+        assert_eq!(coarse, fine);
+        let mut values: Vec<Float> = [3.0, -0.5, 0.25, -7.0, 1e10, -1e-10]
+            .map(|value| Float::from_f64(value, 53, Round::NearestEven))
+            .into_iter()
+            .chain([
+                Float::infinity(Sign::Negative, 3),
+                Float::infinity(Sign::Positive, 3),
+            ])
+            .collect();
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let sorted: Vec<f64> = values.iter().map(Float::to_f64).collect();
+        assert_eq!(
+            sorted,
+            [
+                f64::NEG_INFINITY,
+                -7.0,
+                -0.5,
+                -1e-10,
+                0.25,
+                3.0,
+                1e10,
+                f64::INFINITY
+            ]
+        );
+    }
+    #[test]
+    fn conversions() {
+        let value = Float::from_integer(&Integer::new(-1000i16), 3, Round::NearestEven);
+        assert_eq!(value.to_f64(), -1024.0);
+        let value = Float::from_integer(&Integer::new(-1000i16), 3, Round::TowardZero);
+        assert_eq!(value.to_f64(), -896.0);
+        assert_eq!(Float::from_f64(0.1, 4, Round::Up).to_f64(), 0.1015625);
+        assert_eq!(Float::from_f64(0.1, 4, Round::Down).to_f64(), 0.09375);
+        assert_eq!(
+            Float::from_f64(-0.0, 8, Round::Up).to_f64().to_bits(),
+            (-0.0f64).to_bits()
+        );
+        assert_eq!(exact(f64::MAX).to_f64(), f64::MAX);
+        let min = f64::from_bits(1);
+        for value in [min, 3.0 * min, f64::MIN_POSITIVE, f64::MIN_POSITIVE - min] {
+            assert_eq!(
+                Float::from_f64(value, 60, Round::NearestEven).to_f64(),
+                value
+            );
+        }
+        let half_min = Float::from_rational(&power_of_two(-1075), 10, Round::NearestEven);
+        assert_eq!(half_min.to_f64(), 0.0);
+        let above = half_min.mul_round(
+            &Float::from_f64(1.5, 10, Round::NearestEven),
+            10,
+            Round::NearestEven,
+        );
+        assert_eq!(above.to_f64(), min);
+        let huge = Float::from_rational(&power_of_two(1024), 10, Round::NearestEven);
+        assert_eq!(huge.to_f64(), f64::INFINITY);
+        let rounded = exact(1.0 + f64::EPSILON).with_precision(52, Round::NearestEven);
+        assert_eq!(rounded.to_f64(), 1.0);
+        assert_eq!(rounded.precision(), 52);
+    }
+    #[test]
+    #[should_panic(expected = "precision must be at least one bit")]
+    fn zero_precision() {
+        let _ = Float::nan(0);
+    }
+}
@@ -1,100 +1,422 @@
 #[test]
 fn integer_from_u8() {
     use crate::integer::Integer;
-    assert_eq!(Integer::new(u8::MIN).parts, vec![u8::MIN as usize]);
-    assert_eq!(Integer::new(u8::MAX).parts, vec![u8::MAX as usize]);
-    assert_eq!(Integer::new(42u8).parts, vec![42u8 as usize]);
+    use crate::naturals::Natural;
+    assert_eq!(Integer::new(u8::MIN).magnitude, Natural::ZERO);
+    assert_eq!(Integer::new(u8::MIN).sign, None);
+    assert_eq!(Integer::new(u8::MAX).magnitude.hunks(), [u8::MAX as u64]);
+    assert_eq!(Integer::new(42u8).magnitude.hunks(), [42]);
 }
 
 #[test]
 fn integer_from_u64() {
     use crate::integer::Integer;
-    assert_eq!(Integer::new(u64::MIN).parts, vec![usize::MIN]);
-    assert_eq!(Integer::new(u64::MAX).parts, vec![usize::MAX]);
-    assert_eq!(Integer::new(42u64).parts, vec![42usize]);
+    use crate::naturals::Hunk;
+    assert_eq!(Integer::new(u64::MIN).magnitude.hunks(), []);
+    assert_eq!(Integer::new(u64::MAX).magnitude.hunks(), [Hunk::MAX]);
+    assert_eq!(Integer::new(42u64).magnitude.hunks(), [42]);
 }
 
-// TODO does not work!
 #[test]
-#[ignore]
 fn integer_from_i128() {
     use crate::Sign;
     use crate::integer::Integer;
-    assert_eq!(Integer::new(i128::MIN).parts, vec![usize::MAX, usize::MAX]);
+    use crate::naturals::Hunk;
+    assert_eq!(Integer::new(i128::MIN).magnitude.hunks(), [0, 1 << 63]);
     assert_eq!(Integer::new(i128::MIN).sign, Some(Sign::Negative));
     assert_eq!(
-        Integer::new(i128::MAX).parts,
-        vec![usize::MAX - 1, usize::MAX]
+        Integer::new(i128::MAX).magnitude.hunks(),
+        [Hunk::MAX, Hunk::MAX >> 1]
     );
-    assert_eq!(Integer::new(i128::MAX).sign, Some(Sign::Negative));
-    assert_eq!(Integer::new(42i128).parts, vec![42usize, 0usize]);
+    assert_eq!(Integer::new(i128::MAX).sign, Some(Sign::Positive));
+    assert_eq!(Integer::new(-42i128).magnitude.hunks(), [42]);
+    assert_eq!(Integer::new(-42i128).sign, Some(Sign::Negative));
+    assert_eq!(Integer::new(0i128).sign, None);
 }
 
 #[test]
 fn naturals_from_primitive() {
     use crate::naturals::Natural;
-    if let Natural::Small(small) = Natural::new(8u8) {
-        assert_eq!(8usize, small);
-    } else {
-        panic!("Type u8 is small enough to stay on Stack.");
-    }
+    let small = Natural::new(8u8);
+    assert!(
+        small.is_small(),
+        "Type u8 is small enough to fit into one hunk."
+    );
+    assert_eq!(small.hunks(), [8]);
     assert!(
         Natural::new((u8::MAX as u16) << 3).is_small(),
-        "Type u16 is small enough to stay on Stack."
+        "Type u16 is small enough to fit into one hunk."
     );
-    if let Natural::Small(small) = Natural::new(2147483647u32) {
-        assert_eq!(2147483647usize, small);
-    } else {
-        panic!("Type u32 is small enough to stay on Stack.");
-    }
+    let small = Natural::new(2147483647u32);
+    assert!(
+        small.is_small(),
+        "Type u32 is small enough to fit into one hunk."
+    );
+    assert_eq!(small.first_hunk(), 2147483647);
 }
 #[test]
 fn naturals_from_u128() {
+    use crate::naturals::Hunk;
+    use crate::naturals::Natural;
+    let small = Natural::new(2147483647u128);
+    assert!(
+        small.is_small(),
+        "Given value of type u128 is still small enough to fit into one hunk."
+    );
+    assert_eq!(small.first_hunk(), 2147483647);
+    let big = Natural::new(u128::MAX);
+    assert!(big.is_big(), "This value is split into 2 parts.");
+    assert_eq!(big.hunks(), [Hunk::MAX, Hunk::MAX]);
+    let big = Natural::new((Hunk::MAX as u128) << 32);
+    assert_eq!(big.len(), 2);
+    assert_eq!(big.hunks(), [0xFFFFFFFF00000000, 0xFFFFFFFF]);
+}
+
+#[test]
+fn hunks_are_64_bits_everywhere() {
+    use crate::naturals::{Hunk, Natural};
+    let n = Natural::new(u128::MAX) + Natural::ONE;
+    assert_eq!(Vec::<Hunk>::from(n.clone()), vec![0u64, 0, 1]);
+    assert_eq!(format!("{n:?}"), "Natural([0, 0, 1])");
+    assert_eq!(n.BITS(), Natural::new(129u8));
+    assert_eq!(Natural::new(u64::MAX).len(), 1);
+    assert_eq!(
+        Natural::from_iter([1u64, 2]),
+        (Natural::new(2u8) << 64u8) + Natural::ONE
+    );
+}
+
+#[test]
+fn integer_primitive_round_trips() {
+    use crate::integer::Integer;
+    macro_rules! round_trip {
+        ($($t:ty)*) => ($(
+            for value in [<$t>::MIN, <$t>::MIN / 2, 0, 1, <$t>::MAX / 3, <$t>::MAX] {
+                assert_eq!(<$t>::try_from(Integer::new(value)), Ok(value));
+            }
+        )*)
+    }
+    round_trip! { u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize }
+    assert_eq!(Integer::new(i8::MIN), Integer::new(-128i128));
+    assert_eq!(
+        Integer::new(u128::MAX),
+        -(Integer::new(i128::MIN) * Integer::new(2u8)) - Integer::new(1u8)
+    );
+}
+
+#[test]
+fn integer_and_natural() {
+    use crate::error::TryFromNumberError;
+    use crate::integer::Integer;
+    use crate::naturals::Natural;
+    let n = Natural::new(u128::MAX) * Natural::new(u128::MAX);
+    let i = Integer::from(n.clone());
+    assert_eq!(i.magnitude(), &n);
+    assert_eq!(Natural::try_from(&i), Ok(n.clone()));
+    assert_eq!(Natural::try_from(-i), Err(TryFromNumberError::Negative));
+    assert_eq!(Natural::try_from(Integer::new(0i8)), Ok(Natural::ZERO));
+    assert_eq!(Integer::from(Natural::ZERO).sign(), None);
+    assert_eq!(-Integer::new(0u8), Integer::new(0i8));
+}
+
+#[test]
+fn integer_to_primitives() {
+    use crate::error::TryFromNumberError;
+    use crate::integer::Integer;
+    let n = Integer::new(-129i16);
+    assert_eq!(i16::try_from(&n), Ok(-129));
+    assert_eq!(i8::try_from(&n), Err(TryFromNumberError::Underflow));
+    assert_eq!(u8::try_from(&n), Err(TryFromNumberError::Negative));
+    assert_eq!(n.wrapping_to_i8(), 127);
+    assert_eq!(n.wrapping_to_u16(), (-129i16) as u16);
+    assert_eq!(n.saturating_to_i8(), i8::MIN);
+    assert_eq!(n.saturating_to_u64(), 0);
+    let n = Integer::new(i64::MIN);
+    assert_eq!(i64::try_from(&n), Ok(i64::MIN));
+    assert_eq!(i128::try_from(n), Ok(i64::MIN as i128));
+    let n = Integer::new(u64::MAX);
+    assert_eq!(i64::try_from(&n), Err(TryFromNumberError::Overflow));
+    assert_eq!(n.saturating_to_i64(), i64::MAX);
+    assert_eq!(n.wrapping_to_i64(), -1);
+    assert_eq!(u64::try_from(&n), Ok(u64::MAX));
+    assert_eq!(u8::try_from(Integer::new(0i32)), Ok(0));
+}
+
+#[test]
+fn integer_floats() {
+    use crate::error::TryFromNumberError;
+    use crate::integer::Integer;
+    assert_eq!(Integer::new(-5i8).to_f64(), -5.0);
+    assert_eq!(Integer::new(i64::MIN).to_f32(), i64::MIN as f32);
+    assert_eq!(Integer::new(i64::MIN + 1).to_f64(), i64::MIN as f64);
+    assert_eq!(Integer::new(0u8).to_f64(), 0.0);
+    let n = Integer::try_from(-1e15).unwrap();
+    assert_eq!(i64::try_from(&n), Ok(-1_000_000_000_000_000));
+    assert_eq!(n.to_f64(), -1e15);
+    assert_eq!(
+        i8::try_from(Integer::from_f64_truncate(-7.9).unwrap()),
+        Ok(-7)
+    );
+    assert_eq!(
+        u8::try_from(Integer::from_f64_truncate(-0.9).unwrap()),
+        Ok(0)
+    );
+    for value in [f64::INFINITY, f64::NEG_INFINITY, f64::NAN] {
+        let result = Integer::from_f64_truncate(value);
+        assert!(result.is_err_and(|e| e == TryFromNumberError::NotFinite));
+    }
+    assert!(Integer::try_from(-0.25).is_err_and(|e| e == TryFromNumberError::Fractional));
+    assert!(Integer::try_from(f64::NEG_INFINITY).is_err());
+}
+
+#[test]
+fn integer_add_sub() {
+    use crate::integer::Integer;
+    let cases = [
+        (5i64, 3i64),
+        (-5, 3),
+        (5, -3),
+        (-5, -3),
+        (3, -3),
+        (0, -7),
+        (i64::MIN, -1),
+    ];
+    for (lhs, rhs) in cases {
+        let (a, b) = (Integer::new(lhs), Integer::new(rhs));
+        let (lhs, rhs) = (lhs as i128, rhs as i128);
+        assert_eq!(i128::try_from(&a + &b), Ok(lhs + rhs));
+        assert_eq!(i128::try_from(&a - &b), Ok(lhs - rhs));
+        assert_eq!(i128::try_from(-&a), Ok(-lhs));
+    }
+    let mut n = Integer::new(u64::MAX);
+    n += Integer::new(1u8);
+    assert_eq!(u128::try_from(&n), Ok(1 << 64));
+    n -= Integer::new(u128::MAX);
+    assert_eq!(n, -Integer::new(u128::MAX - (1 << 64)));
+    assert!((Integer::new(-2i8) + Integer::new(2i8)).is_zero());
+}
+
+#[test]
+fn integer_mul_div_rem() {
+    use crate::integer::Integer;
     use crate::naturals::Natural;
-    if let Natural::Small(small) = Natural::new(2147483647u128) {
-        assert_eq!(2147483647usize, small);
-    } else {
-        panic!(
-            "Given value of type u128 is still small enough to fit into usize and can therefore stay on Stack."
-        );
+    for (lhs, rhs) in [(7i64, 2i64), (-7, 2), (7, -2), (-7, -2), (0, -2), (-6, 3)] {
+        let (a, b) = (Integer::new(lhs), Integer::new(rhs));
+        assert_eq!(i64::try_from(&a * &b), Ok(lhs * rhs));
+        assert_eq!(i64::try_from(&a / &b), Ok(lhs / rhs));
+        assert_eq!(i64::try_from(&a % &b), Ok(lhs % rhs));
     }
-    if let Natural::Big(parts) = Natural::new(u128::MAX) {
-        assert_eq!(parts, vec![usize::MAX, usize::MAX]);
-    } else {
-        panic!("This value is not small enough to fit on the stack. Is is split into 2 parts.");
+    let big = Integer::new(-3i8).pow(Natural::new(101u8));
+    let (quotient, remainder) = big.div_rem(&Integer::new(u64::MAX));
+    assert!(quotient.is_negative() && remainder.is_negative());
+    assert_eq!(quotient * Integer::new(u64::MAX) + remainder, big);
+    let mut n = Integer::new(-10i8);
+    n *= Integer::new(-3i8);
+    n /= Integer::new(4i8);
+    n %= Integer::new(-4i8);
+    assert_eq!(i8::try_from(n), Ok(3));
+}
+
+#[test]
+#[should_panic(expected = "division by zero")]
+fn integer_div_by_zero() {
+    use crate::integer::Integer;
+    let _ = Integer::new(-1i8) / Integer::new(0u8);
+}
+
+#[test]
+fn integer_rounding_divisions() {
+    use crate::integer::Integer;
+    let values = [7i64, -7, 6, -6, 1, -1, 0, i64::MAX, i64::MIN + 1];
+    for lhs in values {
+        for rhs in [2i64, -2, 3, -3, 7, -7, 1, -1, i64::MAX] {
+            let (a, b) = (Integer::new(lhs), Integer::new(rhs));
+            let floor = lhs.div_euclid(rhs) - i64::from(rhs < 0 && lhs.rem_euclid(rhs) != 0);
+            let ceil = floor + i64::from(lhs % rhs != 0);
+            assert_eq!(i64::try_from(a.div_floor(&b)), Ok(floor), "{lhs} / {rhs}");
+            assert_eq!(
+                i128::try_from(a.mod_floor(&b)),
+                Ok(lhs as i128 - floor as i128 * rhs as i128)
+            );
+            assert_eq!(i64::try_from(a.div_ceil(&b)), Ok(ceil), "{lhs} / {rhs}");
+            assert_eq!(i64::try_from(a.div_euclid(&b)), Ok(lhs.div_euclid(rhs)));
+            assert_eq!(i64::try_from(a.rem_euclid(&b)), Ok(lhs.rem_euclid(rhs)));
+            for (quotient, remainder) in [
+                a.div_rem(&b),
+                a.div_rem_floor(&b),
+                a.div_rem_ceil(&b),
+                a.div_rem_euclid(&b),
+            ] {
+                assert_eq!(quotient * &b + remainder, a);
+            }
+        }
     }
-    if let Natural::Big(parts) = Natural::new((usize::MAX as u128) << 32) {
-        assert_eq!(parts, vec![0xFFFFFFFF00000000usize, 0xFFFFFFFFusize]);
-    } else {
-        panic!("This value is not small enough to fit on the stack. Is is split into 2 parts.");
+    let (quotient, remainder) = Integer::new(-7i8).div_rem_ceil(&Integer::new(2i8));
+    assert_eq!(
+        (quotient, remainder),
+        (Integer::new(-3i8), Integer::new(-1i8))
+    );
+    let (quotient, remainder) = Integer::new(7i8).div_rem_floor(&Integer::new(-2i8));
+    assert_eq!(
+        (quotient, remainder),
+        (Integer::new(-4i8), Integer::NEG_ONE)
+    );
+}
+
+#[test]
+fn integer_bitwise() {
+    use crate::integer::Integer;
+    use crate::naturals::Hunk;
+    let values = [
+        0i128,
+        1,
+        -1,
+        6,
+        -6,
+        0x5555,
+        -0x5555,
+        1 << 64,
+        -(1 << 64),
+        (1 << 70) - 3,
+        -(1 << 70) + 3,
+        i128::MAX,
+        i128::MIN,
+    ];
+    for lhs in values {
+        for rhs in values {
+            let (a, b) = (Integer::new(lhs), Integer::new(rhs));
+            assert_eq!(&a & &b, Integer::new(lhs & rhs), "{lhs} & {rhs}");
+            assert_eq!(&a | &b, Integer::new(lhs | rhs), "{lhs} | {rhs}");
+            assert_eq!(&a ^ &b, Integer::new(lhs ^ rhs), "{lhs} ^ {rhs}");
+        }
+        assert_eq!(!Integer::new(lhs), Integer::new(!lhs));
     }
+    // -2^200 has 200 trailing zeros and only ones above them.
+    let big = -(Integer::new(1u8) << 200u8);
+    assert_eq!(&big & Integer::new(-1i8), big);
+    assert_eq!(&big | Integer::new(u64::MAX), &big + Integer::new(u64::MAX));
+    assert_eq!(
+        &big ^ Integer::new(-1i8),
+        (Integer::new(1u8) << 200u8) - Integer::ONE
+    );
+    let mut n = Integer::new(-12i8);
+    n &= Integer::new(-7i8);
+    n |= Integer::new(1u8);
+    n ^= Integer::new(3i8);
+    assert_eq!(n, Integer::new((-12i8 & -7 | 1) ^ 3));
+    // 256 hunks of ones, against operands short enough to stay inline.
+    let ones = (Integer::ONE << (256 * Hunk::BITS)) - Integer::ONE;
+    assert_eq!(Integer::new(0x5555) & &ones, Integer::new(0x5555));
+    assert_eq!(&ones & Integer::new(0x5555), Integer::new(0x5555));
+    assert_eq!(Integer::new(-6i8) & &ones, &ones - Integer::new(5u8));
+    let mut n = Integer::new(u128::MAX);
+    n &= &ones;
+    assert_eq!(n, Integer::new(u128::MAX));
 }
 
-// TODO write tests for conversion from signed primitives
+#[test]
+fn integer_shifts() {
+    use crate::integer::Integer;
+    use crate::naturals::Natural;
+    for value in [
+        0i128,
+        1,
+        -1,
+        5,
+        -5,
+        -8,
+        -9,
+        i64::MIN as i128,
+        -(1 << 100) - 1,
+    ] {
+        for shift in [0u32, 1, 2, 3, 63, 64, 65, 126] {
+            assert_eq!(
+                Integer::new(value) >> shift,
+                Integer::new(value >> shift),
+                "{value} >> {shift}"
+            );
+        }
+    }
+    assert_eq!(Integer::new(-1i8) >> 1000u16, Integer::NEG_ONE);
+    assert_eq!(Integer::new(1i8) >> Natural::new(1000u16), Integer::ZERO);
+    assert_eq!(Integer::new(-5i8) << 3u8, Integer::new(-40i8));
+    assert_eq!(
+        Integer::new(-3i8) << &Natural::new(64u8),
+        Integer::new(-3i128 << 64)
+    );
+    let mut n = Integer::new(-7i8);
+    n <<= 2i32;
+    n >>= 3usize;
+    assert_eq!(n, Integer::new(-4i8));
+}
+
+#[test]
+fn integer_ord_hash() {
+    use crate::integer::Integer;
+    use std::collections::HashSet;
+    let mut values: Vec<Integer> = [3i64, -1, 0, i64::MIN, -2, i64::MAX, 1]
+        .into_iter()
+        .map(Integer::new)
+        .collect();
+    values.push(Integer::new(u128::MAX));
+    values.push(-Integer::new(u128::MAX));
+    values.sort();
+    let sorted: Vec<f64> = values.iter().map(Integer::to_f64).collect();
+    assert!(sorted.is_sorted());
+    assert_eq!(values[0], -Integer::new(u128::MAX));
+    assert_eq!(values[8], Integer::new(u128::MAX));
+    assert!(Integer::new(-1i8) < Integer::new(0u8));
+    assert_eq!(Integer::new(42u128), Integer::new(42i8));
+    let set: HashSet<Integer> = [
+        Integer::new(42u128),
+        Integer::new(42i8),
+        -Integer::new(-42i64),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(set.len(), 1);
+}
+
+#[test]
+fn integer_abs_signum_pow() {
+    use crate::integer::Integer;
+    use crate::naturals::Natural;
+    assert_eq!(Integer::new(-5i8).abs(), Integer::new(5u8));
+    assert_eq!(Integer::new(i64::MIN).abs(), Integer::new(1u64 << 63));
+    assert_eq!(i8::try_from(Integer::new(-5i8).signum()), Ok(-1));
+    assert_eq!(i8::try_from(Integer::new(0i8).signum()), Ok(0));
+    assert_eq!(i8::try_from(Integer::new(9u128).signum()), Ok(1));
+    assert_eq!(
+        i64::try_from(Integer::new(-2i8).pow(Natural::new(3u8))),
+        Ok(-8)
+    );
+    assert_eq!(
+        i64::try_from(Integer::new(-2i8).pow(Natural::new(4u8))),
+        Ok(16)
+    );
+    assert_eq!(i64::try_from(Integer::new(-2i8).pow(Natural::ZERO)), Ok(1));
+    assert_eq!(
+        Integer::new(-2i8).pow(Natural::new(127u8)),
+        -Integer::new(1u128 << 127)
+    );
+}
 
 #[test]
 fn trim() {
-    use crate::naturals::Natural::{Big, Small};
-    assert_eq!(vec![2usize, 0usize], vec![2usize, 0usize]);
-    assert_eq!(Small(2usize), Big(vec![2usize, 0usize]).trim());
+    use crate::naturals::Hunk;
+    use crate::naturals::Natural;
+    assert_eq!(vec![2, 0], vec![2, 0]);
+    assert_eq!(Natural::from_hunk(2), Natural::from_hunks(vec![2, 0]));
     assert_eq!(
-        Small(usize::MAX),
-        Big(vec![usize::MAX, 0usize, 0usize, 0usize]).trim()
+        Natural::from_hunk(Hunk::MAX),
+        Natural::from_hunks(vec![Hunk::MAX, 0, 0, 0])
     );
     assert_eq!(
-        Big(vec![1usize << 8usize, usize::MAX]),
-        Big(vec![1usize << 8usize, usize::MAX, 0usize, 0usize]).trim()
+        Natural::from_hunks(vec![1 << 8, Hunk::MAX]),
+        Natural::from_hunks(vec![1 << 8, Hunk::MAX, 0, 0])
     );
     assert_eq!(
-        Big(vec![1usize << 8usize, 0usize, usize::MAX]),
-        Big(vec![
-            1usize << 8usize,
-            0usize,
-            usize::MAX,
-            0usize,
-            0usize,
-            0usize,
-        ])
-        .trim()
+        Natural::from_hunks(vec![1 << 8, 0, Hunk::MAX]),
+        Natural::from_hunks(vec![1 << 8, 0, Hunk::MAX, 0, 0, 0,])
     );
 }